@@ -5,8 +5,9 @@ use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 use cosmwasm_std::Response;
 
 use reflect::msg::{
-    CapitalizedResponse, ChainResponse, CustomMsg, HandleMsg, InitMsg, OwnerResponse, QueryMsg,
-    RawResponse,
+    CapitalizedBatchResponse, CapitalizedResponse, ChainResponse, ChainTypedResponse, CustomMsg,
+    HandleMsg, InitMsg, IntrospectResponse, MigrateMsg, OwnerResponse, QueryMsg,
+    RawChunkedResponse, RawResponse, RemoteStateResponse, SimulateReflectResponse,
 };
 use reflect::state::State;
 
@@ -19,6 +20,7 @@ fn main() {
     export_schema(&schema_for!(CustomMsg), &out_dir);
     export_schema(&schema_for!(InitMsg), &out_dir);
     export_schema(&schema_for!(HandleMsg), &out_dir);
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
     export_schema(&schema_for!(Response<CustomMsg>), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
     export_schema(&schema_for!(State), &out_dir);
@@ -26,6 +28,12 @@ fn main() {
     // The possible return types for QueryMsg cases
     export_schema(&schema_for!(OwnerResponse), &out_dir);
     export_schema(&schema_for!(CapitalizedResponse), &out_dir);
+    export_schema(&schema_for!(CapitalizedBatchResponse), &out_dir);
     export_schema(&schema_for!(ChainResponse), &out_dir);
+    export_schema(&schema_for!(ChainTypedResponse), &out_dir);
     export_schema(&schema_for!(RawResponse), &out_dir);
+    export_schema(&schema_for!(RawChunkedResponse), &out_dir);
+    export_schema(&schema_for!(RemoteStateResponse), &out_dir);
+    export_schema(&schema_for!(SimulateReflectResponse), &out_dir);
+    export_schema(&schema_for!(IntrospectResponse), &out_dir);
 }
@@ -1,23 +1,42 @@
 use cosmwasm_std::{
-    attr, to_binary, to_vec, Binary, ContractResult, CosmosMsg, Deps, DepsMut, Env, HumanAddr,
-    MessageInfo, QueryRequest, QueryResponse, Response, StdError, StdResult, SystemResult, WasmMsg,
+    attr, raw_binary, to_binary, to_vec, BankMsg, BankQuery, Binary, CanonicalAddr, Coin,
+    ContractResult, CosmosMsg, Deps, DepsMut, Env, HumanAddr, MessageInfo, QueryRequest,
+    QueryResponse, Reply, Response, StakingMsg, StdError, StdResult, Storage, SystemError,
+    SystemResult, Uint128, WasmMsg, WasmQuery,
 };
 
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::fmt;
+
 use crate::errors::ReflectError;
 use crate::msg::{
-    CallbackMsg, CapitalizedResponse, ChainResponse, CustomMsg, HandleMsg, InitMsg, OwnerResponse,
-    QueryMsg, RawResponse, SpecialQuery, SpecialResponse,
+    CallbackMsg, CapitalizedBatchResponse, CapitalizedResponse, ChainResponse, ChainTypedResponse,
+    CustomMsg, HandleMsg, InitMsg, IntrospectResponse, MigrateMsg, OwnerResponse, QueryMsg,
+    RawChunkedResponse, RawResponse, RemoteStateResponse, SimulateReflectResponse, SpecialQuery,
+    SpecialResponse,
+};
+use crate::state::{
+    config, config_raw_key, config_read, executions, executions_read, replies, State,
+    MAX_OWNER_LENGTH, STATE_VERSION,
 };
-use crate::state::{config, config_read, State};
 
 pub fn init(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     msg: InitMsg,
-) -> StdResult<Response<CustomMsg>> {
+) -> Result<Response<CustomMsg>, ReflectError> {
     let state = State {
         owner: deps.api.canonical_address(&info.sender)?,
+        paused: false,
+        renounced: false,
+        version: STATE_VERSION,
+        reject_self_calls: false,
+        verify_validators: false,
+        validate_denoms: false,
     };
     config(deps.storage).save(&state)?;
 
@@ -29,7 +48,7 @@ pub fn init(
         };
         let msg = WasmMsg::Execute {
             contract_addr: info.sender,
-            msg: to_binary(&data)?,
+            msg: to_binary_with_context(&data, "init_callback")?,
             send: vec![],
         };
         resp.add_message(msg);
@@ -37,6 +56,38 @@ pub fn init(
     Ok(resp)
 }
 
+/// Like `to_binary` but reports errors as `ReflectError::Serialization` with the given context
+fn to_binary_with_context<T: Serialize + ?Sized>(
+    data: &T,
+    context: &str,
+) -> Result<Binary, ReflectError> {
+    to_binary(data).map_err(|err| ReflectError::Serialization {
+        context: context.to_string(),
+        msg: err.to_string(),
+    })
+}
+
+/// Upgrades state written by older code to `STATE_VERSION`.
+/// Fields added in the meantime get their default values when loading.
+pub fn migrate(
+    deps: DepsMut,
+    _env: Env,
+    _msg: MigrateMsg,
+) -> Result<Response<CustomMsg>, ReflectError> {
+    let mut state = load_state(deps.storage)?;
+    let from_version = state.version;
+    state.version = STATE_VERSION;
+    config(deps.storage).save(&state)?;
+    Ok(Response {
+        attributes: vec![
+            attr("action", "migrate"),
+            attr("from_version", from_version),
+            attr("to_version", STATE_VERSION),
+        ],
+        ..Response::default()
+    })
+}
+
 pub fn handle(
     deps: DepsMut,
     env: Env,
@@ -45,34 +96,218 @@ pub fn handle(
 ) -> Result<Response<CustomMsg>, ReflectError> {
     match msg {
         HandleMsg::ReflectMsg { msgs } => try_reflect(deps, env, info, msgs),
+        HandleMsg::ReflectMsgOnce { key, msgs } => try_reflect_once(deps, env, info, key, msgs),
+        HandleMsg::ReflectMsgWithFunds { msgs } => try_reflect_with_funds(deps, env, info, msgs),
         HandleMsg::ChangeOwner { owner } => try_change_owner(deps, env, info, owner),
+        HandleMsg::SetPaused { paused } => try_set_paused(deps, env, info, paused),
+        HandleMsg::SetRejectSelfCalls { reject } => {
+            try_set_reject_self_calls(deps, env, info, reject)
+        }
+        HandleMsg::SetVerifyValidators { verify } => {
+            try_set_verify_validators(deps, env, info, verify)
+        }
+        HandleMsg::SetValidateDenoms { validate } => {
+            try_set_validate_denoms(deps, env, info, validate)
+        }
+        HandleMsg::RenounceOwnership {} => try_renounce_ownership(deps, env, info),
+        HandleMsg::MaybeSend { recipient, amount } => {
+            try_maybe_send(deps, env, info, recipient, amount)
+        }
     }
 }
 
 pub fn try_reflect(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msgs: Vec<CosmosMsg<CustomMsg>>,
 ) -> Result<Response<CustomMsg>, ReflectError> {
-    let state = config(deps.storage).load()?;
-
-    let sender = deps.api.canonical_address(&info.sender)?;
-    if sender != state.owner {
-        return Err(ReflectError::NotCurrentOwner {
-            expected: state.owner,
-            actual: sender,
-        });
-    }
+    let state = load_reflect_state(deps.as_ref(), &info)?;
 
     if msgs.is_empty() {
         return Err(ReflectError::MessagesEmpty);
     }
-    let res = Response {
-        messages: msgs,
+    for (index, msg) in msgs.iter().enumerate() {
+        validate_message(msg, &state).map_err(|err| ReflectError::InvalidMessageAt {
+            index,
+            reason: err.to_string(),
+        })?;
+    }
+    if state.reject_self_calls {
+        if let Some(index) = find_self_call(&env, &msgs) {
+            return Err(ReflectError::SelfCall { index });
+        }
+    }
+    if state.verify_validators {
+        if let Some((index, validator)) = find_unknown_validator(deps.as_ref(), &msgs)? {
+            return Err(ReflectError::UnknownValidator { index, validator });
+        }
+    }
+    Ok(Response {
         attributes: vec![attr("action", "reflect")],
-        data: None,
-    };
+        ..Response::with_messages(msgs)
+    })
+}
+
+/// Returns the index of the first message that executes this contract
+fn find_self_call(env: &Env, msgs: &[CosmosMsg<CustomMsg>]) -> Option<usize> {
+    msgs.iter()
+        .position(|msg| msg.targets_self(&env.contract.address))
+}
+
+/// Returns the index and validator of the first delegation to a validator the chain does not know.
+/// The validators are only queried if `msgs` contains a delegation.
+fn find_unknown_validator(
+    deps: Deps,
+    msgs: &[CosmosMsg<CustomMsg>],
+) -> StdResult<Option<(usize, HumanAddr)>> {
+    let delegations: Vec<(usize, &HumanAddr)> = msgs
+        .iter()
+        .enumerate()
+        .filter_map(|(index, msg)| match msg {
+            CosmosMsg::Staking(StakingMsg::Delegate { validator, .. }) => Some((index, validator)),
+            _ => None,
+        })
+        .collect();
+    if delegations.is_empty() {
+        return Ok(None);
+    }
+    let known = deps.querier.query_validators()?;
+    let unknown = delegations
+        .into_iter()
+        .find(|(_, validator)| !known.iter().any(|v| v.address == **validator));
+    Ok(unknown.map(|(index, validator)| (index, validator.clone())))
+}
+
+/// Checks a single message before it is reflected. Currently this only covers the denoms
+/// of bank messages, which are checked if `validate_denoms` is set.
+fn validate_message(msg: &CosmosMsg<CustomMsg>, state: &State) -> StdResult<()> {
+    if state.validate_denoms {
+        validate_denoms(msg)?;
+    }
+    Ok(())
+}
+
+/// Checks the denoms of bank messages against the Cosmos SDK rules
+fn validate_denoms(msg: &CosmosMsg<CustomMsg>) -> StdResult<()> {
+    match msg {
+        CosmosMsg::Bank(BankMsg::Send { amount, .. }) => {
+            for coin in amount.iter() {
+                coin.validate_denom()?;
+            }
+        }
+        CosmosMsg::Bank(BankMsg::MultiSend { outputs }) => {
+            for coin in outputs.iter().flat_map(|output| output.coins.iter()) {
+                coin.validate_denom()?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Loads the state and ensures `info.sender` may reflect messages
+fn load_reflect_state(deps: Deps, info: &MessageInfo) -> Result<State, ReflectError> {
+    let state = load_state(deps.storage)?;
+    assert_owner(&state, deps.api.canonical_address(&info.sender)?)?;
+    if state.paused {
+        return Err(ReflectError::Paused);
+    }
+    Ok(state)
+}
+
+pub fn try_reflect_once(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    key: String,
+    msgs: Vec<CosmosMsg<CustomMsg>>,
+) -> Result<Response<CustomMsg>, ReflectError> {
+    if let Some(data) = executions_read(deps.storage).may_load(key.as_bytes())? {
+        load_reflect_state(deps.as_ref(), &info)?;
+
+        // already executed, so we only return the previous data
+        return Ok(Response {
+            messages: vec![],
+            attributes: vec![
+                attr("action", "reflect_once"),
+                attr("key", key),
+                attr("replayed", "true"),
+            ],
+            data,
+        });
+    }
+
+    let mut res = try_reflect(deps.branch(), env, info, msgs)?;
+    // the JSON of the reflected messages, which tells a replaying caller what was executed
+    res.data = Some(to_binary(&res.messages)?);
+    executions(deps.storage).save(key.as_bytes(), &res.data)?;
+    res.attributes = vec![attr("action", "reflect_once"), attr("key", key)];
+    Ok(res)
+}
+
+pub fn try_reflect_with_funds(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msgs: Vec<CosmosMsg<CustomMsg>>,
+) -> Result<Response<CustomMsg>, ReflectError> {
+    let funds = info.funds.clone();
+    let mut res = try_reflect(deps, env, info, msgs)?;
+    if !funds.is_empty() {
+        forward_funds(&mut res.messages, funds)?;
+    }
+    Ok(res)
+}
+
+/// Adds `funds` to the amount of the first `BankMsg::Send` in `msgs`, merging coins of the same denom
+fn forward_funds(msgs: &mut [CosmosMsg<CustomMsg>], funds: Vec<Coin>) -> Result<(), ReflectError> {
+    let amount = msgs
+        .iter_mut()
+        .find_map(|msg| match msg {
+            CosmosMsg::Bank(BankMsg::Send { amount, .. }) => Some(amount),
+            _ => None,
+        })
+        .ok_or(ReflectError::FundsWithoutSend)?;
+    for fund in funds {
+        match amount.iter_mut().find(|coin| coin.denom == fund.denom) {
+            Some(coin) => {
+                let sum = coin
+                    .amount
+                    .u128()
+                    .checked_add(fund.amount.u128())
+                    .ok_or_else(|| {
+                        StdError::overflow(format!("Cannot add {} to {}", fund, coin))
+                    })?;
+                coin.amount = Uint128(sum);
+            }
+            None => amount.push(fund),
+        }
+    }
+    Ok(())
+}
+
+pub fn try_maybe_send(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    recipient: HumanAddr,
+    amount: Option<Coin>,
+) -> Result<Response<CustomMsg>, ReflectError> {
+    load_reflect_state(deps.as_ref(), &info)?;
+
+    let mut res = Response::new();
+    res.add_attribute("action", "maybe_send");
+    match amount {
+        Some(amount) if !amount.amount.is_zero() => {
+            res.add_attribute("amount", amount.to_string());
+            res.add_message(BankMsg::Send {
+                to_address: recipient,
+                amount: vec![amount],
+            });
+        }
+        _ => {}
+    }
     Ok(res)
 }
 
@@ -83,15 +318,11 @@ pub fn try_change_owner(
     owner: HumanAddr,
 ) -> Result<Response<CustomMsg>, ReflectError> {
     let api = deps.api;
+    let owner = owner.to_normalized();
     config(deps.storage).update(|mut state| {
-        let sender = api.canonical_address(&info.sender)?;
-        if sender != state.owner {
-            return Err(ReflectError::NotCurrentOwner {
-                expected: state.owner,
-                actual: sender,
-            });
-        }
-        state.owner = api.canonical_address(&owner)?;
+        check_state(&state)?;
+        assert_owner(&state, api.canonical_address(&info.sender)?)?;
+        state.owner = api.addr_validate_to_canonical(&owner)?;
         Ok(state)
     })?;
     Ok(Response {
@@ -100,62 +331,435 @@ pub fn try_change_owner(
     })
 }
 
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<QueryResponse> {
-    match msg {
-        QueryMsg::Owner {} => to_binary(&query_owner(deps)?),
-        QueryMsg::Capitalized { text } => to_binary(&query_capitalized(deps, text)?),
-        QueryMsg::Chain { request } => to_binary(&query_chain(deps, &request)?),
-        QueryMsg::Raw { contract, key } => to_binary(&query_raw(deps, contract, key)?),
+pub fn try_set_paused(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    paused: bool,
+) -> Result<Response<CustomMsg>, ReflectError> {
+    let api = deps.api;
+    config(deps.storage).update(|mut state| {
+        check_state(&state)?;
+        assert_owner(&state, api.canonical_address(&info.sender)?)?;
+        state.paused = paused;
+        Ok(state)
+    })?;
+    Ok(Response {
+        attributes: vec![attr("action", "set_paused"), attr("paused", paused)],
+        ..Response::default()
+    })
+}
+
+pub fn try_set_reject_self_calls(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    reject: bool,
+) -> Result<Response<CustomMsg>, ReflectError> {
+    let api = deps.api;
+    config(deps.storage).update(|mut state| {
+        check_state(&state)?;
+        assert_owner(&state, api.canonical_address(&info.sender)?)?;
+        state.reject_self_calls = reject;
+        Ok(state)
+    })?;
+    Ok(Response {
+        attributes: vec![
+            attr("action", "set_reject_self_calls"),
+            attr("reject", reject),
+        ],
+        ..Response::default()
+    })
+}
+
+pub fn try_set_verify_validators(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    verify: bool,
+) -> Result<Response<CustomMsg>, ReflectError> {
+    let api = deps.api;
+    config(deps.storage).update(|mut state| {
+        check_state(&state)?;
+        assert_owner(&state, api.canonical_address(&info.sender)?)?;
+        state.verify_validators = verify;
+        Ok(state)
+    })?;
+    Ok(Response {
+        attributes: vec![
+            attr("action", "set_verify_validators"),
+            attr("verify", verify),
+        ],
+        ..Response::default()
+    })
+}
+
+pub fn try_set_validate_denoms(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    validate: bool,
+) -> Result<Response<CustomMsg>, ReflectError> {
+    let api = deps.api;
+    config(deps.storage).update(|mut state| {
+        check_state(&state)?;
+        assert_owner(&state, api.canonical_address(&info.sender)?)?;
+        state.validate_denoms = validate;
+        Ok(state)
+    })?;
+    Ok(Response {
+        attributes: vec![
+            attr("action", "set_validate_denoms"),
+            attr("validate", validate),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Permanently gives up ownership. Afterwards all owner-only actions fail.
+pub fn try_renounce_ownership(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+) -> Result<Response<CustomMsg>, ReflectError> {
+    let api = deps.api;
+    config(deps.storage).update(|mut state| {
+        check_state(&state)?;
+        assert_owner(&state, api.canonical_address(&info.sender)?)?;
+        state.renounced = true;
+        Ok(state)
+    })?;
+    Ok(Response {
+        attributes: vec![attr("action", "renounce_ownership")],
+        ..Response::default()
+    })
+}
+
+/// Loads the state and rejects it if `check_state` fails
+fn load_state(storage: &dyn Storage) -> Result<State, ReflectError> {
+    let state = config_read(storage).load()?;
+    check_state(&state)?;
+    Ok(state)
+}
+
+/// Rejects state written by newer code, which this code cannot interpret, as well as corrupted state
+fn check_state(state: &State) -> Result<(), ReflectError> {
+    check_state_version(state)?;
+    check_owner_length(state)
+}
+
+fn check_state_version(state: &State) -> Result<(), ReflectError> {
+    if state.version > STATE_VERSION {
+        return Err(ReflectError::UnsupportedStateVersion {
+            version: state.version,
+            supported: STATE_VERSION,
+        });
+    }
+    Ok(())
+}
+
+fn check_owner_length(state: &State) -> Result<(), ReflectError> {
+    let length = state.owner.len();
+    if length == 0 || length > MAX_OWNER_LENGTH {
+        return Err(ReflectError::InvalidOwnerLength { length });
+    }
+    Ok(())
+}
+
+/// Ensures that `sender` is the current owner and ownership has not been renounced
+fn assert_owner(state: &State, sender: CanonicalAddr) -> Result<(), ReflectError> {
+    if state.renounced {
+        return Err(ReflectError::OwnershipRenounced);
     }
+    if sender != state.owner {
+        return Err(ReflectError::NotCurrentOwner {
+            expected: state.owner.clone(),
+            actual: sender,
+        });
+    }
+    Ok(())
+}
+
+/// Stores the result of a submessage execution by its id
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response<CustomMsg>, ReflectError> {
+    let key = msg.id.to_be_bytes();
+    replies(deps.storage).save(&key, &msg)?;
+    let data = match msg.result {
+        ContractResult::Ok(response) => response.data,
+        ContractResult::Err(_) => None,
+    };
+    Ok(Response::from_reply_data(data))
 }
 
-fn query_owner(deps: Deps) -> StdResult<OwnerResponse> {
-    let state = config_read(deps.storage).load()?;
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, ReflectError> {
+    let response = match msg {
+        QueryMsg::Owner {} => to_binary(&query_owner(deps, env)?)?,
+        QueryMsg::Capitalized { text } => to_binary(&query_capitalized(deps, text)?)?,
+        QueryMsg::CapitalizedBatch { texts } => to_binary(&query_capitalized_batch(deps, texts)?)?,
+        QueryMsg::Chain { request } => to_binary(&query_chain(deps, &request)?)?,
+        QueryMsg::ChainTyped { request } => to_binary(&query_chain_typed(deps, &request)?)?,
+        QueryMsg::ChainSmart { contract_addr, msg } => query_chain_smart(deps, contract_addr, msg)?,
+        QueryMsg::Raw { contract, key } => to_binary(&query_raw(deps, contract, key)?)?,
+        QueryMsg::RawChunked {
+            contract,
+            key,
+            offset,
+            limit,
+        } => to_binary(&query_raw_chunked(deps, contract, key, offset, limit)?)?,
+        QueryMsg::SimulateReflect { msgs } => to_binary(&query_simulate_reflect(deps, env, msgs)?)?,
+        QueryMsg::RemoteState { contract } => to_binary(&query_remote_state(deps, contract)?)?,
+        QueryMsg::Introspect {} => to_binary(&query_introspect())?,
+    };
+    Ok(response)
+}
+
+fn query_owner(deps: Deps, env: Env) -> Result<OwnerResponse, ReflectError> {
+    let state = load_state(deps.storage)?;
     let resp = OwnerResponse {
         owner: deps.api.human_address(&state.owner)?,
+        block_height: Some(env.block.height),
+        renounced: state.renounced,
     };
     Ok(resp)
 }
 
+/// The message families that can be reflected, as named by `message_family`
+pub const MESSAGE_FAMILIES: &[&str] = &["bank", "custom", "staking", "stargate", "ibc", "wasm"];
+
+/// The optional features implemented by this contract
+pub const FEATURES: &[&str] = &[
+    "paused",
+    "reflect-once",
+    "reject-self-calls",
+    "renounce-ownership",
+    "validate-denoms",
+    "verify-validators",
+];
+
+/// Returns the name of the family a message belongs to, or `None` for variants
+/// this contract does not know about
+fn message_family<T>(msg: &CosmosMsg<T>) -> Option<&'static str>
+where
+    T: Clone + fmt::Debug + PartialEq + JsonSchema,
+{
+    match msg {
+        CosmosMsg::Bank(_) => Some("bank"),
+        CosmosMsg::Custom(_) => Some("custom"),
+        CosmosMsg::Staking(_) => Some("staking"),
+        CosmosMsg::Stargate { .. } => Some("stargate"),
+        CosmosMsg::Ibc(_) => Some("ibc"),
+        CosmosMsg::Wasm(_) => Some("wasm"),
+        _ => None,
+    }
+}
+
+/// Counts the messages per family as named by `message_family`.
+/// Messages of unknown families are counted as "unknown".
+pub fn message_type_counts<T>(msgs: &[CosmosMsg<T>]) -> BTreeMap<&'static str, usize>
+where
+    T: Clone + fmt::Debug + PartialEq + JsonSchema,
+{
+    let mut counts = BTreeMap::new();
+    for msg in msgs {
+        let family = message_family(msg).unwrap_or("unknown");
+        *counts.entry(family).or_insert(0) += 1;
+    }
+    counts
+}
+
+fn query_introspect() -> IntrospectResponse {
+    IntrospectResponse {
+        message_families: MESSAGE_FAMILIES.iter().map(|f| f.to_string()).collect(),
+        features: FEATURES.iter().map(|f| f.to_string()).collect(),
+    }
+}
+
 fn query_capitalized(deps: Deps, text: String) -> StdResult<CapitalizedResponse> {
     let req = SpecialQuery::Capitalized { text }.into();
     let response: SpecialResponse = deps.querier.custom_query(&req)?;
     Ok(CapitalizedResponse { text: response.msg })
 }
 
-fn query_chain(deps: Deps, request: &QueryRequest<SpecialQuery>) -> StdResult<ChainResponse> {
+fn query_capitalized_batch(deps: Deps, texts: Vec<String>) -> StdResult<CapitalizedBatchResponse> {
+    let texts = texts
+        .into_iter()
+        .map(|text| query_capitalized(deps, text).map(|response| response.text))
+        .collect::<StdResult<Vec<String>>>()?;
+    Ok(CapitalizedBatchResponse { texts })
+}
+
+/// The maximum size in bytes of a serialized request forwarded by the `Chain` query
+pub const MAX_CHAIN_REQUEST_SIZE: usize = 16 * 1024;
+
+fn query_chain(
+    deps: Deps,
+    request: &QueryRequest<SpecialQuery>,
+) -> Result<ChainResponse, ReflectError> {
+    let data = query_chain_raw(deps, request)?;
+    Ok(ChainResponse { data })
+}
+
+/// The result of a smart query already is JSON, so we can return it without re-encoding
+fn query_chain_smart(
+    deps: Deps,
+    contract_addr: HumanAddr,
+    msg: Binary,
+) -> Result<QueryResponse, ReflectError> {
+    let request = WasmQuery::Smart { contract_addr, msg }.into();
+    query_chain_raw(deps, &request)
+}
+
+fn query_chain_raw(
+    deps: Deps,
+    request: &QueryRequest<SpecialQuery>,
+) -> Result<Binary, ReflectError> {
     let raw = to_vec(request).map_err(|serialize_err| {
         StdError::generic_err(format!("Serializing QueryRequest: {}", serialize_err))
     })?;
+    check_request_size(&raw, MAX_CHAIN_REQUEST_SIZE)?;
     match deps.querier.raw_query(&raw) {
-        SystemResult::Err(system_err) => Err(StdError::generic_err(format!(
-            "Querier system error: {}",
-            system_err
-        ))),
-        SystemResult::Ok(ContractResult::Err(contract_err)) => Err(StdError::generic_err(format!(
-            "Querier contract error: {}",
-            contract_err
-        ))),
-        SystemResult::Ok(ContractResult::Ok(value)) => Ok(ChainResponse { data: value }),
+        SystemResult::Err(SystemError::NoSuchContract { addr }) => {
+            Err(ReflectError::NoSuchContract { addr })
+        }
+        SystemResult::Err(system_err) => {
+            Err(StdError::generic_err(format!("Querier system error: {}", system_err)).into())
+        }
+        SystemResult::Ok(ContractResult::Err(contract_err)) => {
+            Err(StdError::generic_err(format!("Querier contract error: {}", contract_err)).into())
+        }
+        SystemResult::Ok(ContractResult::Ok(value)) => Ok(value),
+    }
+}
+
+/// Fails early for requests that exceed `max` bytes, before any gas is spent on sending them
+fn check_request_size(raw: &[u8], max: usize) -> Result<(), ReflectError> {
+    if raw.len() > max {
+        return Err(ReflectError::RequestTooLarge {
+            size: raw.len(),
+            max,
+        });
+    }
+    Ok(())
+}
+
+fn query_chain_typed(
+    deps: Deps,
+    request: &QueryRequest<SpecialQuery>,
+) -> StdResult<ChainTypedResponse> {
+    match request {
+        QueryRequest::Bank(BankQuery::Balance { .. }) => Ok(ChainTypedResponse::Balance(
+            deps.querier.custom_query(request)?,
+        )),
+        QueryRequest::Bank(BankQuery::AllBalances { .. }) => Ok(ChainTypedResponse::AllBalances(
+            deps.querier.custom_query(request)?,
+        )),
+        QueryRequest::Custom(SpecialQuery::Random { .. }) => Ok(ChainTypedResponse::Random(
+            deps.querier.custom_query(request)?,
+        )),
+        QueryRequest::Custom(_) => Ok(ChainTypedResponse::Special(
+            deps.querier.custom_query(request)?,
+        )),
+        _ => Err(StdError::generic_err(
+            "Unsupported request kind for typed chain query",
+        )),
     }
 }
 
 fn query_raw(deps: Deps, contract: HumanAddr, key: Binary) -> StdResult<RawResponse> {
     let response: Option<Vec<u8>> = deps.querier.query_wasm_raw(contract, key)?;
     Ok(RawResponse {
-        data: response.unwrap_or_default().into(),
+        data: raw_binary(response.unwrap_or_default()),
+    })
+}
+
+/// Collects all errors `try_reflect` could return for `msgs`, except for owner errors
+fn query_simulate_reflect(
+    deps: Deps,
+    env: Env,
+    msgs: Vec<CosmosMsg<CustomMsg>>,
+) -> Result<SimulateReflectResponse, ReflectError> {
+    let state = load_state(deps.storage)?;
+
+    let mut errors: Vec<ReflectError> = vec![];
+    if state.paused {
+        errors.push(ReflectError::Paused);
+    }
+    if msgs.is_empty() {
+        errors.push(ReflectError::MessagesEmpty);
+    }
+    for (index, msg) in msgs.iter().enumerate() {
+        if let Err(err) = validate_message(msg, &state).and_then(|_| to_vec(msg)) {
+            errors.push(ReflectError::InvalidMessageAt {
+                index,
+                reason: err.to_string(),
+            });
+        }
+    }
+    if state.reject_self_calls {
+        if let Some(index) = find_self_call(&env, &msgs) {
+            errors.push(ReflectError::SelfCall { index });
+        }
+    }
+    if state.verify_validators {
+        if let Some((index, validator)) = find_unknown_validator(deps, &msgs)? {
+            errors.push(ReflectError::UnknownValidator { index, validator });
+        }
+    }
+    Ok(SimulateReflectResponse {
+        valid: errors.is_empty(),
+        errors: errors.iter().map(|err| err.to_string()).collect(),
+    })
+}
+
+fn query_remote_state(deps: Deps, contract: HumanAddr) -> StdResult<RemoteStateResponse> {
+    let state: Option<State> = deps
+        .querier
+        .query_wasm_raw_typed(contract, config_raw_key())?;
+    Ok(RemoteStateResponse { state })
+}
+
+fn query_raw_chunked(
+    deps: Deps,
+    contract: HumanAddr,
+    key: Binary,
+    offset: u32,
+    limit: u32,
+) -> StdResult<RawChunkedResponse> {
+    // The complete value is loaded, so only the response size is bounded, not the query cost
+    let value = deps
+        .querier
+        .query_wasm_raw(contract, key)?
+        .unwrap_or_default();
+    let total_len = u32::try_from(value.len())?;
+    let start = value.len().min(offset as usize);
+    let end = value.len().min(start.saturating_add(limit as usize));
+    Ok(RawChunkedResponse {
+        data: raw_binary(&value[start..end]),
+        total_len,
     })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::testing::mock_dependencies_with_custom_querier;
-    use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+    use crate::msg::{DebugLevel, RandomResponse};
+    use crate::state::replies_read;
+    use crate::testing::{
+        mock_dependencies_at_height, mock_dependencies_with_balances,
+        mock_dependencies_with_custom_querier,
+    };
+    use cosmwasm_std::testing::{
+        mock_env, mock_env_at_height, mock_env_with_contract, mock_info, MockApi, MockStorage,
+        MOCK_CONTRACT_ADDR,
+    };
     use cosmwasm_std::{
-        coin, coins, from_binary, AllBalanceResponse, Api, BankMsg, BankQuery, Binary, StakingMsg,
-        StdError,
+        attr, coin, coins, from_binary, from_slice, wasm_query, AllBalanceResponse, Api,
+        BalanceResponse, BankQuery, Binary, Decimal, DelegationResponse, Empty, Event,
+        FullDelegation, IbcMsg, Output, Querier, QuerierResult, QuerierWrapper, StakingQuery,
+        StdError, SubMsgExecutionResponse, Validator,
     };
+    use cosmwasm_storage::to_length_prefixed;
+    use serde::Deserialize;
 
     #[test]
     fn proper_initialization() {
@@ -169,10 +773,27 @@ mod tests {
         assert_eq!(0, res.messages.len());
 
         // it worked, let's query the state
-        let value = query_owner(deps.as_ref()).unwrap();
+        let value = query_owner(deps.as_ref(), mock_env()).unwrap();
         assert_eq!("creator", value.owner.as_str());
     }
 
+    #[test]
+    fn owner_query_contains_block_height() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
+
+        let msg = InitMsg { callback_id: None };
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let response = query(deps.as_ref(), mock_env(), QueryMsg::Owner {}).unwrap();
+        let value: OwnerResponse = from_binary(&response).unwrap();
+        assert_eq!(value.block_height, Some(mock_env().block.height));
+
+        // responses without height can still be parsed
+        let value: OwnerResponse = from_binary(&br#"{"owner":"creator"}"#.into()).unwrap();
+        assert_eq!(value.block_height, None);
+    }
+
     #[test]
     fn init_with_callback() {
         let mut deps = mock_dependencies_with_custom_querier(&[]);
@@ -186,6 +807,7 @@ mod tests {
         // we can just call .unwrap() to assert this was a success
         let res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
         assert_eq!(1, res.messages.len());
+        assert!(res.contains_message(|m| matches!(m, CosmosMsg::Wasm(WasmMsg::Execute { .. }))));
         let msg = &res.messages[0];
         match msg {
             CosmosMsg::Wasm(WasmMsg::Execute {
@@ -208,78 +830,297 @@ mod tests {
         }
 
         // it worked, let's query the state
-        let value = query_owner(deps.as_ref()).unwrap();
+        let value = query_owner(deps.as_ref(), mock_env()).unwrap();
         assert_eq!(caller, value.owner);
     }
 
     #[test]
-    fn reflect() {
+    fn init_callback_contains_contract_address() {
         let mut deps = mock_dependencies_with_custom_querier(&[]);
 
-        let msg = InitMsg { callback_id: None };
-        let info = mock_info("creator", &coins(2, "token"));
-        let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
+        for address in &["contract-one", "contract-two"] {
+            let msg = InitMsg {
+                callback_id: Some("foobar".to_string()),
+            };
+            let info = mock_info("calling-contract", &[]);
+            let env = mock_env_with_contract(*address);
+            let res = init(deps.as_mut(), env, info, msg).unwrap();
+            match &res.messages[0] {
+                CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => {
+                    let parsed: CallbackMsg = from_binary(msg).unwrap();
+                    assert_eq!(
+                        parsed,
+                        CallbackMsg::InitCallback {
+                            id: "foobar".to_string(),
+                            contract_addr: HumanAddr::from(*address),
+                        }
+                    );
+                }
+                _ => panic!("expect wasm execute message"),
+            }
+        }
+    }
 
-        let payload = vec![BankMsg::Send {
-            to_address: HumanAddr::from("friend"),
-            amount: coins(1, "token"),
+    #[test]
+    fn to_binary_with_context_reports_context() {
+        struct Unserializable {}
+
+        impl Serialize for Unserializable {
+            fn serialize<S: serde::Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+                Err(serde::ser::Error::custom("cannot serialize this"))
+            }
         }
-        .into()];
 
-        let msg = HandleMsg::ReflectMsg {
+        let err = to_binary_with_context(&Unserializable {}, "init_callback").unwrap_err();
+        match err {
+            ReflectError::Serialization { context, msg } => {
+                assert_eq!(context, "init_callback");
+                assert!(msg.contains("cannot serialize this"));
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn reflect() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
+
+        let msg = InitMsg { callback_id: None };
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let payload = vec![BankMsg::Send {
+            to_address: HumanAddr::from("friend"),
+            amount: coins(1, "token"),
+        }
+        .into()];
+
+        let msg = HandleMsg::ReflectMsg {
             msgs: payload.clone(),
         };
         let info = mock_info("creator", &[]);
         let res = handle(deps.as_mut(), mock_env(), info, msg).unwrap();
         assert_eq!(payload, res.messages);
+        assert_eq!(res.attribute("action"), Some("reflect"));
     }
 
     #[test]
-    fn reflect_requires_owner() {
+    fn reflect_with_funds_forwards_into_first_send() {
         let mut deps = mock_dependencies_with_custom_querier(&[]);
 
         let msg = InitMsg { callback_id: None };
         let info = mock_info("creator", &coins(2, "token"));
         let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        // signer is not owner
-        let payload = vec![BankMsg::Send {
+        let payload: Vec<CosmosMsg<CustomMsg>> = vec![
+            CustomMsg::Raw(Binary(b"{}".to_vec())).into(),
+            BankMsg::Send {
+                to_address: HumanAddr::from("friend"),
+                amount: coins(1, "token"),
+            }
+            .into(),
+            BankMsg::Send {
+                to_address: HumanAddr::from("other"),
+                amount: coins(5, "token"),
+            }
+            .into(),
+        ];
+        let msg = HandleMsg::ReflectMsgWithFunds {
+            msgs: payload.clone(),
+        };
+        let info = mock_info("creator", &[coin(10, "token"), coin(3, "earth")]);
+        let res = handle(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                payload[0].clone(),
+                BankMsg::Send {
+                    to_address: HumanAddr::from("friend"),
+                    amount: vec![coin(11, "token"), coin(3, "earth")],
+                }
+                .into(),
+                payload[2].clone(),
+            ]
+        );
+
+        // without funds the messages are reflected as they are
+        let msg = HandleMsg::ReflectMsgWithFunds {
+            msgs: payload.clone(),
+        };
+        let res = handle(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(res.messages, payload);
+    }
+
+    #[test]
+    fn reflect_with_funds_requires_send() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
+
+        let msg = InitMsg { callback_id: None };
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let payload: Vec<CosmosMsg<CustomMsg>> = vec![StakingMsg::Delegate {
+            validator: HumanAddr::from("validator"),
+            amount: coin(100, "ustake"),
+        }
+        .into()];
+        let msg = HandleMsg::ReflectMsgWithFunds { msgs: payload };
+        let info = mock_info("creator", &coins(10, "token"));
+        let err = handle(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(err, ReflectError::FundsWithoutSend);
+    }
+
+    #[test]
+    fn reflect_with_funds_rejects_overflow() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
+
+        let msg = InitMsg { callback_id: None };
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let payload: Vec<CosmosMsg<CustomMsg>> = vec![BankMsg::Send {
             to_address: HumanAddr::from("friend"),
-            amount: coins(1, "token"),
+            amount: coins(u128::MAX, "token"),
         }
         .into()];
+        let msg = HandleMsg::ReflectMsgWithFunds { msgs: payload };
+        let info = mock_info("creator", &coins(1, "token"));
+        let err = handle(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        match err {
+            ReflectError::Std(StdError::Overflow { msg, .. }) => {
+                assert_eq!(msg, format!("Cannot add 1token to {}token", u128::MAX))
+            }
+            e => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn reflect_rejects_self_calls_if_enabled() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
+
+        let msg = InitMsg { callback_id: None };
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let call = |contract_addr: &str| -> CosmosMsg<CustomMsg> {
+            WasmMsg::Execute {
+                contract_addr: HumanAddr::from(contract_addr),
+                msg: Binary::from(b"{}"),
+                send: vec![],
+            }
+            .into()
+        };
+        let self_call = vec![call("other"), call(MOCK_CONTRACT_ADDR)];
+        let other_call = vec![call("other")];
+
+        // allowed by default
         let msg = HandleMsg::ReflectMsg {
-            msgs: payload.clone(),
+            msgs: self_call.clone(),
         };
+        let res = handle(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(res.messages, self_call);
 
-        let info = mock_info("random", &[]);
-        let res = handle(deps.as_mut(), mock_env(), info, msg);
-        match res.unwrap_err() {
+        // only the owner can enable the check
+        let msg = HandleMsg::SetRejectSelfCalls { reject: true };
+        let err = handle(deps.as_mut(), mock_env(), mock_info("random", &[]), msg).unwrap_err();
+        match err {
             ReflectError::NotCurrentOwner { .. } => {}
-            err => panic!("Unexpected error: {:?}", err),
+            e => panic!("Unexpected error: {:?}", e),
         }
+        let msg = HandleMsg::SetRejectSelfCalls { reject: true };
+        let res = handle(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "set_reject_self_calls"),
+                attr("reject", "true")
+            ]
+        );
+
+        let msg = HandleMsg::ReflectMsg { msgs: self_call };
+        let err = handle(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err();
+        assert_eq!(err, ReflectError::SelfCall { index: 1 });
+        assert_eq!(err.to_string(), "Message at index 1 calls this contract");
+
+        let msg = HandleMsg::ReflectMsg {
+            msgs: other_call.clone(),
+        };
+        let res = handle(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(res.messages, other_call);
     }
 
     #[test]
-    fn reflect_reject_empty_msgs() {
+    fn reflect_rejects_unknown_validators_if_enabled() {
         let mut deps = mock_dependencies_with_custom_querier(&[]);
+        let validator = Validator {
+            address: HumanAddr::from("known"),
+            commission: Decimal::percent(1),
+            max_commission: Decimal::percent(3),
+            max_change_rate: Decimal::percent(1),
+        };
+        deps.querier.update_staking("ustake", &[validator], &[]);
 
         let msg = InitMsg { callback_id: None };
         let info = mock_info("creator", &coins(2, "token"));
         let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        let info = mock_info("creator", &[]);
-        let payload = vec![];
+        let delegate = |validator: &str| -> CosmosMsg<CustomMsg> {
+            StakingMsg::Delegate {
+                validator: HumanAddr::from(validator),
+                amount: coin(100, "ustake"),
+            }
+            .into()
+        };
+        let unknown = vec![delegate("known"), delegate("unknown")];
+        let known = vec![delegate("known")];
 
+        // allowed by default
         let msg = HandleMsg::ReflectMsg {
-            msgs: payload.clone(),
+            msgs: unknown.clone(),
         };
-        let err = handle(deps.as_mut(), mock_env(), info, msg).unwrap_err();
-        assert_eq!(err, ReflectError::MessagesEmpty);
+        let res = handle(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(res.messages, unknown);
+
+        // only the owner can enable the check
+        let msg = HandleMsg::SetVerifyValidators { verify: true };
+        let err = handle(deps.as_mut(), mock_env(), mock_info("random", &[]), msg).unwrap_err();
+        match err {
+            ReflectError::NotCurrentOwner { .. } => {}
+            e => panic!("Unexpected error: {:?}", e),
+        }
+        let msg = HandleMsg::SetVerifyValidators { verify: true };
+        let res = handle(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "set_verify_validators"),
+                attr("verify", "true")
+            ]
+        );
+
+        let msg = HandleMsg::ReflectMsg { msgs: unknown };
+        let err = handle(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err();
+        assert_eq!(
+            err,
+            ReflectError::UnknownValidator {
+                index: 1,
+                validator: HumanAddr::from("unknown"),
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "Message at index 1 delegates to unknown validator unknown"
+        );
+
+        let msg = HandleMsg::ReflectMsg {
+            msgs: known.clone(),
+        };
+        let res = handle(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(res.messages, known);
     }
 
     #[test]
-    fn reflect_multiple_messages() {
+    fn reflect_multiple_messages_snapshot() {
         let mut deps = mock_dependencies_with_custom_querier(&[]);
 
         let msg = InitMsg { callback_id: None };
@@ -292,124 +1133,1675 @@ mod tests {
                 amount: coins(1, "token"),
             }
             .into(),
-            // make sure we can pass through custom native messages
-            CustomMsg::Raw(Binary(b"{\"foo\":123}".to_vec())).into(),
-            CustomMsg::Debug("Hi, Dad!".to_string()).into(),
-            StakingMsg::Delegate {
-                validator: HumanAddr::from("validator"),
-                amount: coin(100, "ustake"),
+            CustomMsg::Debug {
+                level: DebugLevel::Info,
+                msg: "Hi, Dad!".to_string(),
             }
             .into(),
         ];
-
-        let msg = HandleMsg::ReflectMsg {
-            msgs: payload.clone(),
-        };
+        let msg = HandleMsg::ReflectMsg { msgs: payload };
         let info = mock_info("creator", &[]);
         let res = handle(deps.as_mut(), mock_env(), info, msg).unwrap();
-        assert_eq!(payload, res.messages);
+        assert_eq!(
+            res.to_canonical_json(),
+            r#"{"messages":[{"bank":{"send":{"to_address":"friend","amount":[{"denom":"token","amount":"1"}]}}},{"custom":{"debug":{"level":"info","msg":"Hi, Dad!"}}}],"attributes":[{"key":"action","value":"reflect"}],"data":null}"#
+        );
     }
 
     #[test]
-    fn change_owner_works() {
+    fn reflect_once_executes_once_per_key() {
         let mut deps = mock_dependencies_with_custom_querier(&[]);
 
         let msg = InitMsg { callback_id: None };
         let info = mock_info("creator", &coins(2, "token"));
         let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
 
+        let payload: Vec<CosmosMsg<CustomMsg>> = vec![BankMsg::Send {
+            to_address: HumanAddr::from("friend"),
+            amount: coins(1, "token"),
+        }
+        .into()];
+
+        // first call executes
+        let msg = HandleMsg::ReflectMsgOnce {
+            key: "payout-1".to_string(),
+            msgs: payload.clone(),
+        };
         let info = mock_info("creator", &[]);
-        let new_owner = HumanAddr::from("friend");
-        let msg = HandleMsg::ChangeOwner {
-            owner: new_owner.clone(),
+        let res = handle(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+        assert_eq!(res.messages, payload);
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "reflect_once"), attr("key", "payout-1")]
+        );
+        let executed = Some(to_binary(&payload).unwrap());
+        assert_eq!(res.data, executed);
+
+        // second call with the same key is a no-op
+        let res = handle(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        assert_eq!(res.messages.len(), 0);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "reflect_once"),
+                attr("key", "payout-1"),
+                attr("replayed", "true")
+            ]
+        );
+        assert_eq!(res.data, executed);
+
+        // a new key executes again
+        let msg = HandleMsg::ReflectMsgOnce {
+            key: "payout-2".to_string(),
+            msgs: payload.clone(),
         };
         let res = handle(deps.as_mut(), mock_env(), info, msg).unwrap();
-
-        // should change state
-        assert_eq!(0, res.messages.len());
-        let value = query_owner(deps.as_ref()).unwrap();
-        assert_eq!("friend", value.owner.as_str());
+        assert_eq!(res.messages, payload);
     }
 
     #[test]
-    fn change_owner_requires_current_owner_as_sender() {
+    fn reflect_once_replay_respects_pause_and_renounce() {
         let mut deps = mock_dependencies_with_custom_querier(&[]);
 
         let msg = InitMsg { callback_id: None };
-        let creator = HumanAddr::from("creator");
-        let info = mock_info(&creator, &coins(2, "token"));
+        let info = mock_info("creator", &coins(2, "token"));
         let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        let random = HumanAddr::from("random");
-        let info = mock_info(&random, &[]);
-        let new_owner = HumanAddr::from("friend");
-        let msg = HandleMsg::ChangeOwner {
-            owner: new_owner.clone(),
+        let msg = HandleMsg::ReflectMsgOnce {
+            key: "payout-1".to_string(),
+            msgs: vec![BankMsg::Send {
+                to_address: HumanAddr::from("friend"),
+                amount: coins(1, "token"),
+            }
+            .into()],
         };
+        let info = mock_info("creator", &[]);
+        handle(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
 
+        // replaying is blocked while paused
+        let pause = HandleMsg::SetPaused { paused: true };
+        handle(deps.as_mut(), mock_env(), info.clone(), pause).unwrap();
+        let err = handle(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap_err();
+        assert_eq!(err, ReflectError::Paused);
+
+        // and after renouncing ownership
+        let unpause = HandleMsg::SetPaused { paused: false };
+        handle(deps.as_mut(), mock_env(), info.clone(), unpause).unwrap();
+        let renounce = HandleMsg::RenounceOwnership {};
+        handle(deps.as_mut(), mock_env(), info.clone(), renounce).unwrap();
         let err = handle(deps.as_mut(), mock_env(), info, msg).unwrap_err();
-        let expected = deps.api.canonical_address(&creator).unwrap();
-        let actual = deps.api.canonical_address(&random).unwrap();
-        assert_eq!(err, ReflectError::NotCurrentOwner { expected, actual });
+        assert_eq!(err, ReflectError::OwnershipRenounced);
     }
 
     #[test]
-    fn change_owner_errors_for_invalid_new_address() {
+    fn reflect_once_requires_owner() {
         let mut deps = mock_dependencies_with_custom_querier(&[]);
-        let creator = HumanAddr::from("creator");
 
         let msg = InitMsg { callback_id: None };
-        let info = mock_info(&creator, &coins(2, "token"));
+        let info = mock_info("creator", &coins(2, "token"));
         let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        let info = mock_info(&creator, &[]);
-        let msg = HandleMsg::ChangeOwner {
-            owner: HumanAddr::from("x"),
+        let msg = HandleMsg::ReflectMsgOnce {
+            key: "payout-1".to_string(),
+            msgs: vec![CustomMsg::Debug {
+                level: DebugLevel::Info,
+                msg: "Hi".to_string(),
+            }
+            .into()],
         };
-        let err = handle(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        let res = handle(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            msg.clone(),
+        );
+        assert!(res.is_ok());
+
+        // replaying a known key requires ownership as well
+        let err = handle(deps.as_mut(), mock_env(), mock_info("random", &[]), msg).unwrap_err();
         match err {
-            ReflectError::Std(StdError::GenericErr { msg, .. }) => {
-                assert!(msg.contains("human address too short"))
-            }
-            e => panic!("Unexpected error: {:?}", e),
+            ReflectError::NotCurrentOwner { .. } => {}
+            err => panic!("Unexpected error: {:?}", err),
         }
     }
 
     #[test]
-    fn capitalized_query_works() {
-        let deps = mock_dependencies_with_custom_querier(&[]);
+    fn reflect_requires_owner() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
 
-        let msg = QueryMsg::Capitalized {
-            text: "demo one".to_string(),
+        let msg = InitMsg { callback_id: None };
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // signer is not owner
+        let payload = vec![BankMsg::Send {
+            to_address: HumanAddr::from("friend"),
+            amount: coins(1, "token"),
+        }
+        .into()];
+        let msg = HandleMsg::ReflectMsg {
+            msgs: payload.clone(),
         };
-        let response = query(deps.as_ref(), mock_env(), msg).unwrap();
-        let value: CapitalizedResponse = from_binary(&response).unwrap();
-        assert_eq!(value.text, "DEMO ONE");
+
+        let info = mock_info("random", &[]);
+        let res = handle(deps.as_mut(), mock_env(), info, msg);
+        match res.unwrap_err() {
+            ReflectError::NotCurrentOwner { .. } => {}
+            err => panic!("Unexpected error: {:?}", err),
+        }
     }
 
     #[test]
-    fn chain_query_works() {
-        let deps = mock_dependencies_with_custom_querier(&coins(123, "ucosm"));
+    fn reflect_reject_empty_msgs() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
 
-        // with bank query
-        let msg = QueryMsg::Chain {
-            request: BankQuery::AllBalances {
-                address: HumanAddr::from(MOCK_CONTRACT_ADDR),
-            }
-            .into(),
+        let msg = InitMsg { callback_id: None };
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("creator", &[]);
+        let payload = vec![];
+
+        let msg = HandleMsg::ReflectMsg {
+            msgs: payload.clone(),
         };
-        let response = query(deps.as_ref(), mock_env(), msg).unwrap();
-        let outer: ChainResponse = from_binary(&response).unwrap();
-        let inner: AllBalanceResponse = from_binary(&outer.data).unwrap();
-        assert_eq!(inner.amount, coins(123, "ucosm"));
+        let err = handle(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(err, ReflectError::MessagesEmpty);
+    }
 
-        // with custom query
-        let msg = QueryMsg::Chain {
-            request: SpecialQuery::Ping {}.into(),
+    #[test]
+    fn reflect_rejects_invalid_denom() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
+
+        let msg = InitMsg { callback_id: None };
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let payload: Vec<CosmosMsg<CustomMsg>> = vec![BankMsg::Send {
+            to_address: HumanAddr::from("friend"),
+            amount: coins(1, "to ken"),
+        }
+        .into()];
+
+        // allowed by default, since chains may use other denom formats
+        let msg = HandleMsg::ReflectMsg {
+            msgs: payload.clone(),
         };
-        let response = query(deps.as_ref(), mock_env(), msg).unwrap();
-        let outer: ChainResponse = from_binary(&response).unwrap();
-        let inner: SpecialResponse = from_binary(&outer.data).unwrap();
-        assert_eq!(inner.msg, "pong");
+        let res = handle(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(res.messages, payload);
+
+        // only the owner can enable the check
+        let msg = HandleMsg::SetValidateDenoms { validate: true };
+        let err = handle(deps.as_mut(), mock_env(), mock_info("random", &[]), msg).unwrap_err();
+        match err {
+            ReflectError::NotCurrentOwner { .. } => {}
+            e => panic!("Unexpected error: {:?}", e),
+        }
+        let msg = HandleMsg::SetValidateDenoms { validate: true };
+        let res = handle(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "set_validate_denoms"),
+                attr("validate", "true")
+            ]
+        );
+
+        let msg = HandleMsg::ReflectMsg { msgs: payload };
+        let info = mock_info("creator", &[]);
+        let err = handle(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        match err {
+            ReflectError::InvalidMessageAt { index, reason } => {
+                assert_eq!(index, 0);
+                assert!(reason.contains("Invalid denom"))
+            }
+            e => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn reflect_reports_index_of_invalid_message() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
+
+        let msg = InitMsg { callback_id: None };
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let msg = HandleMsg::SetValidateDenoms { validate: true };
+        handle(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let payload = vec![
+            BankMsg::Send {
+                to_address: HumanAddr::from("friend"),
+                amount: coins(1, "token"),
+            }
+            .into(),
+            CustomMsg::Raw(Binary(b"{\"foo\":123}".to_vec())).into(),
+            BankMsg::MultiSend {
+                outputs: vec![
+                    Output {
+                        address: HumanAddr::from("alice"),
+                        coins: coins(1, "token"),
+                    },
+                    Output {
+                        address: HumanAddr::from("bob"),
+                        coins: coins(2, "1token"),
+                    },
+                ],
+            }
+            .into(),
+            BankMsg::Send {
+                to_address: HumanAddr::from("friend"),
+                amount: coins(1, "t"),
+            }
+            .into(),
+        ];
+        let msg = HandleMsg::ReflectMsg { msgs: payload };
+        let info = mock_info("creator", &[]);
+        let err = handle(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(
+            err,
+            ReflectError::InvalidMessageAt {
+                index: 2,
+                reason: "Generic error: Invalid denom '1token': must start with a letter"
+                    .to_string(),
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "Invalid message at index 2: Generic error: Invalid denom '1token': must start with a letter"
+        );
+    }
+
+    #[test]
+    fn simulate_reflect_reports_problems() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
+
+        let msg = InitMsg { callback_id: None };
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let simulate = |deps: Deps, msgs: Vec<CosmosMsg<CustomMsg>>| -> SimulateReflectResponse {
+            let response = query(deps, mock_env(), QueryMsg::SimulateReflect { msgs }).unwrap();
+            from_binary(&response).unwrap()
+        };
+
+        // valid batch
+        let payload: Vec<CosmosMsg<CustomMsg>> = vec![
+            BankMsg::Send {
+                to_address: HumanAddr::from("friend"),
+                amount: coins(1, "token"),
+            }
+            .into(),
+            CustomMsg::Raw(Binary(b"{\"foo\":123}".to_vec())).into(),
+        ];
+        let report = simulate(deps.as_ref(), payload.clone());
+        assert_eq!(
+            report,
+            SimulateReflectResponse {
+                valid: true,
+                errors: vec![],
+            }
+        );
+
+        // invalid batch, all problems are reported
+        let mut invalid = payload;
+        invalid.push(
+            BankMsg::Send {
+                to_address: HumanAddr::from("friend"),
+                amount: coins(1, "1token"),
+            }
+            .into(),
+        );
+        invalid.push(
+            BankMsg::Send {
+                to_address: HumanAddr::from("friend"),
+                amount: coins(1, "t"),
+            }
+            .into(),
+        );
+        // denoms are only checked if enabled
+        let report = simulate(deps.as_ref(), invalid.clone());
+        assert_eq!(
+            report,
+            SimulateReflectResponse {
+                valid: true,
+                errors: vec![],
+            }
+        );
+        let msg = HandleMsg::SetValidateDenoms { validate: true };
+        handle(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let report = simulate(deps.as_ref(), invalid);
+        assert_eq!(
+            report,
+            SimulateReflectResponse {
+                valid: false,
+                errors: vec![
+                    "Invalid message at index 2: Generic error: Invalid denom '1token': must start with a letter".to_string(),
+                    "Invalid message at index 3: Generic error: Invalid denom 't': length must be between 3 and 128".to_string(),
+                ],
+            }
+        );
+
+        // the contract state is taken into account
+        let msg = HandleMsg::SetPaused { paused: true };
+        handle(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let report = simulate(deps.as_ref(), vec![]);
+        assert_eq!(
+            report,
+            SimulateReflectResponse {
+                valid: false,
+                errors: vec![
+                    "Contract is paused".to_string(),
+                    "Messages empty. Must reflect at least one message".to_string(),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn std_errors_are_converted_with_question_mark() {
+        // storage: state not yet initialized
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
+        let msg = HandleMsg::ReflectMsg {
+            msgs: vec![CustomMsg::Debug {
+                level: DebugLevel::Info,
+                msg: "Hi".to_string(),
+            }
+            .into()],
+        };
+        let err = handle(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err();
+        assert_eq!(
+            err,
+            ReflectError::Std(StdError::not_found("reflect::state::State"))
+        );
+
+        // address conversion: sender address too short
+        let msg = InitMsg { callback_id: None };
+        let err = init(deps.as_mut(), mock_env(), mock_info("x", &[]), msg).unwrap_err();
+        match err {
+            ReflectError::Std(StdError::GenericErr { msg, .. }) => {
+                assert!(msg.contains("human address too short"))
+            }
+            e => panic!("Unexpected error: {:?}", e),
+        }
+
+        // the StdError is transparent, so its message is shown as is
+        let err = handle(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            HandleMsg::SetPaused { paused: true },
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "reflect::state::State not found");
+        assert!(std::error::Error::source(&err).is_none());
+    }
+
+    #[test]
+    fn reflect_multiple_messages() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
+
+        let msg = InitMsg { callback_id: None };
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let payload = vec![
+            BankMsg::Send {
+                to_address: HumanAddr::from("friend"),
+                amount: coins(1, "token"),
+            }
+            .into(),
+            // make sure we can pass through custom native messages
+            CustomMsg::Raw(Binary(b"{\"foo\":123}".to_vec())).into(),
+            CustomMsg::Debug {
+                level: DebugLevel::Info,
+                msg: "Hi, Dad!".to_string(),
+            }
+            .into(),
+            StakingMsg::Delegate {
+                validator: HumanAddr::from("validator"),
+                amount: coin(100, "ustake"),
+            }
+            .into(),
+        ];
+
+        let msg = HandleMsg::ReflectMsg {
+            msgs: payload.clone(),
+        };
+        let info = mock_info("creator", &[]);
+        let res = handle(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(payload, res.messages);
+
+        let counts = message_type_counts(&res.messages);
+        let expected: BTreeMap<&str, usize> = vec![("bank", 1), ("custom", 2), ("staking", 1)]
+            .into_iter()
+            .collect();
+        assert_eq!(counts, expected);
+    }
+
+    #[test]
+    fn message_type_counts_works_for_empty_list() {
+        let msgs: Vec<CosmosMsg<CustomMsg>> = vec![];
+        assert!(message_type_counts(&msgs).is_empty());
+    }
+
+    #[test]
+    fn reflect_response_maps_to_empty() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
+
+        let msg = InitMsg { callback_id: None };
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let send = BankMsg::Send {
+            to_address: HumanAddr::from("friend"),
+            amount: coins(1, "token"),
+        };
+        let payload = vec![
+            send.clone().into(),
+            CustomMsg::Raw(Binary(b"{\"foo\":123}".to_vec())).into(),
+            CustomMsg::Debug {
+                level: DebugLevel::Info,
+                msg: "Hi, Dad!".to_string(),
+            }
+            .into(),
+        ];
+        let msg = HandleMsg::ReflectMsg { msgs: payload };
+        let info = mock_info("creator", &[]);
+        let res = handle(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // drop raw messages and turn debug messages into empty ones
+        let mapped: Response<Empty> = res.map_custom(|msg| match msg {
+            CustomMsg::Raw(_) => None,
+            CustomMsg::Debug { .. } => Some(Empty {}),
+        });
+        assert_eq!(
+            mapped.messages,
+            vec![CosmosMsg::Bank(send), CosmosMsg::Custom(Empty {})]
+        );
+        assert_eq!(mapped.attributes, vec![attr("action", "reflect")]);
+    }
+
+    #[test]
+    fn reflect_multi_send() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
+
+        let msg = InitMsg { callback_id: None };
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let payload = vec![BankMsg::MultiSend {
+            outputs: vec![
+                Output {
+                    address: HumanAddr::from("alice"),
+                    coins: coins(1, "token"),
+                },
+                Output {
+                    address: HumanAddr::from("bob"),
+                    coins: coins(2, "token"),
+                },
+                Output {
+                    address: HumanAddr::from("carol"),
+                    coins: coins(3, "token"),
+                },
+            ],
+        }
+        .into()];
+
+        let msg = HandleMsg::ReflectMsg {
+            msgs: payload.clone(),
+        };
+        let info = mock_info("creator", &[]);
+        let res = handle(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(payload, res.messages);
+    }
+
+    #[test]
+    fn reflect_admin_messages() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
+
+        let msg = InitMsg { callback_id: None };
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let payload = vec![
+            WasmMsg::UpdateAdmin {
+                contract_addr: HumanAddr::from("managed"),
+                admin: HumanAddr::from("new-admin"),
+            }
+            .into(),
+            WasmMsg::ClearAdmin {
+                contract_addr: HumanAddr::from("managed"),
+            }
+            .into(),
+        ];
+
+        let msg = HandleMsg::ReflectMsg {
+            msgs: payload.clone(),
+        };
+        let info = mock_info("creator", &[]);
+        let res = handle(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(payload, res.messages);
+    }
+
+    #[test]
+    fn reflect_staking_messages() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
+
+        let msg = InitMsg { callback_id: None };
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let payload = vec![
+            StakingMsg::Delegate {
+                validator: HumanAddr::from("validator"),
+                amount: coin(100, "ustake"),
+            }
+            .into(),
+            StakingMsg::Undelegate {
+                validator: HumanAddr::from("validator"),
+                amount: coin(40, "ustake"),
+            }
+            .into(),
+        ];
+
+        let msg = HandleMsg::ReflectMsg {
+            msgs: payload.clone(),
+        };
+        let info = mock_info("creator", &[]);
+        let res = handle(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(payload, res.messages);
+    }
+
+    #[test]
+    fn maybe_send_works() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
+
+        let msg = InitMsg { callback_id: None };
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // no amount
+        let msg = HandleMsg::MaybeSend {
+            recipient: HumanAddr::from("friend"),
+            amount: None,
+        };
+        let info = mock_info("creator", &[]);
+        let res = handle(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        // zero amount
+        let msg = HandleMsg::MaybeSend {
+            recipient: HumanAddr::from("friend"),
+            amount: Some(coin(0, "token")),
+        };
+        let info = mock_info("creator", &[]);
+        let res = handle(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        // positive amount
+        let msg = HandleMsg::MaybeSend {
+            recipient: HumanAddr::from("friend"),
+            amount: Some(coin(1, "token")),
+        };
+        let info = mock_info("creator", &[]);
+        let res = handle(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![BankMsg::Send {
+                to_address: HumanAddr::from("friend"),
+                amount: coins(1, "token"),
+            }
+            .into()]
+        );
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "maybe_send"), attr("amount", "1token")]
+        );
+    }
+
+    #[test]
+    fn maybe_send_requires_owner() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
+
+        let msg = InitMsg { callback_id: None };
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = HandleMsg::MaybeSend {
+            recipient: HumanAddr::from("friend"),
+            amount: Some(coin(1, "token")),
+        };
+        let info = mock_info("random", &[]);
+        let err = handle(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        match err {
+            ReflectError::NotCurrentOwner { .. } => {}
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn change_owner_works() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
+
+        let msg = InitMsg { callback_id: None };
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("creator", &[]);
+        let new_owner = HumanAddr::from("friend");
+        let msg = HandleMsg::ChangeOwner {
+            owner: new_owner.clone(),
+        };
+        let res = handle(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // should change state
+        assert_eq!(0, res.messages.len());
+        let value = query_owner(deps.as_ref(), mock_env()).unwrap();
+        assert_eq!("friend", value.owner.as_str());
+    }
+
+    #[test]
+    fn change_owner_normalizes_address() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
+
+        let msg = InitMsg { callback_id: None };
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("creator", &[]);
+        let msg = HandleMsg::ChangeOwner {
+            owner: HumanAddr::from("FrIeNd"),
+        };
+        let res = handle(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "change_owner"), attr("owner", "friend")]
+        );
+
+        // mixed-case and lowercase input result in the same canonical owner
+        let state = load_state(&deps.storage).unwrap();
+        let expected = deps
+            .api
+            .canonical_address(&HumanAddr::from("friend"))
+            .unwrap();
+        assert_eq!(state.owner, expected);
+
+        // the lowercase address can act as owner
+        let info = mock_info("friend", &[]);
+        let msg = HandleMsg::ChangeOwner {
+            owner: HumanAddr::from("creator"),
+        };
+        handle(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    #[test]
+    fn change_owner_requires_current_owner_as_sender() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
+
+        let msg = InitMsg { callback_id: None };
+        let creator = HumanAddr::from("creator");
+        let info = mock_info(&creator, &coins(2, "token"));
+        let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let random = HumanAddr::from("random");
+        let info = mock_info(&random, &[]);
+        let new_owner = HumanAddr::from("friend");
+        let msg = HandleMsg::ChangeOwner {
+            owner: new_owner.clone(),
+        };
+
+        let err = handle(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        let expected = deps.api.canonical_address(&creator).unwrap();
+        let actual = deps.api.canonical_address(&random).unwrap();
+        assert_eq!(err, ReflectError::NotCurrentOwner { expected, actual });
+    }
+
+    #[test]
+    fn change_owner_errors_for_invalid_new_address() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
+        let creator = HumanAddr::from("creator");
+
+        let msg = InitMsg { callback_id: None };
+        let info = mock_info(&creator, &coins(2, "token"));
+        let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info(&creator, &[]);
+        let msg = HandleMsg::ChangeOwner {
+            owner: HumanAddr::from("x"),
+        };
+        let err = handle(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        match err {
+            ReflectError::Std(StdError::InvalidAddress { addr, msg, .. }) => {
+                assert_eq!(addr, "x");
+                assert!(msg.contains("human address too short"));
+            }
+            e => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn set_paused_blocks_reflect() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
+
+        let msg = InitMsg { callback_id: None };
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let payload: Vec<CosmosMsg<CustomMsg>> = vec![BankMsg::Send {
+            to_address: HumanAddr::from("friend"),
+            amount: coins(1, "token"),
+        }
+        .into()];
+        let reflect_msg = HandleMsg::ReflectMsg {
+            msgs: payload.clone(),
+        };
+        let info = mock_info("creator", &[]);
+
+        // pause
+        let msg = HandleMsg::SetPaused { paused: true };
+        let res = handle(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "set_paused"), attr("paused", "true")]
+        );
+        let err = handle(deps.as_mut(), mock_env(), info.clone(), reflect_msg.clone()).unwrap_err();
+        assert_eq!(err, ReflectError::Paused);
+
+        // sending tokens is blocked as well
+        let send_msg = HandleMsg::MaybeSend {
+            recipient: HumanAddr::from("friend"),
+            amount: Some(coin(1, "token")),
+        };
+        let err = handle(deps.as_mut(), mock_env(), info.clone(), send_msg.clone()).unwrap_err();
+        assert_eq!(err, ReflectError::Paused);
+
+        // queries still work
+        let value = query_owner(deps.as_ref(), mock_env()).unwrap();
+        assert_eq!("creator", value.owner.as_str());
+
+        // unpause
+        let msg = HandleMsg::SetPaused { paused: false };
+        let _res = handle(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let res = handle(deps.as_mut(), mock_env(), info.clone(), reflect_msg).unwrap();
+        assert_eq!(payload, res.messages);
+        let res = handle(deps.as_mut(), mock_env(), info, send_msg).unwrap();
+        assert_eq!(res.messages.len(), 1);
+    }
+
+    #[test]
+    fn set_paused_requires_owner() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
+
+        let msg = InitMsg { callback_id: None };
+        let creator = HumanAddr::from("creator");
+        let info = mock_info(&creator, &coins(2, "token"));
+        let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let random = HumanAddr::from("random");
+        let info = mock_info(&random, &[]);
+        let msg = HandleMsg::SetPaused { paused: true };
+        let err = handle(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        let expected = deps.api.canonical_address(&creator).unwrap();
+        let actual = deps.api.canonical_address(&random).unwrap();
+        assert_eq!(err, ReflectError::NotCurrentOwner { expected, actual });
+
+        let state = config_read(&deps.storage).load().unwrap();
+        assert!(!state.paused);
+    }
+
+    #[test]
+    fn renounce_ownership_blocks_owner_actions() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
+
+        let msg = InitMsg { callback_id: None };
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let response = query(deps.as_ref(), mock_env(), QueryMsg::Owner {}).unwrap();
+        let value: OwnerResponse = from_binary(&response).unwrap();
+        assert!(!value.renounced);
+
+        let info = mock_info("creator", &[]);
+        let res = handle(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            HandleMsg::RenounceOwnership {},
+        )
+        .unwrap();
+        assert_eq!(res.attributes, vec![attr("action", "renounce_ownership")]);
+
+        let response = query(deps.as_ref(), mock_env(), QueryMsg::Owner {}).unwrap();
+        let value: OwnerResponse = from_binary(&response).unwrap();
+        assert_eq!(value.owner.as_str(), "creator");
+        assert!(value.renounced);
+
+        // all owner actions fail permanently
+        let msgs = vec![
+            HandleMsg::ReflectMsg {
+                msgs: vec![CustomMsg::Debug {
+                    level: DebugLevel::Info,
+                    msg: "Hi".to_string(),
+                }
+                .into()],
+            },
+            HandleMsg::ChangeOwner {
+                owner: HumanAddr::from("friend"),
+            },
+            HandleMsg::SetPaused { paused: true },
+            HandleMsg::MaybeSend {
+                recipient: HumanAddr::from("friend"),
+                amount: None,
+            },
+            HandleMsg::RenounceOwnership {},
+        ];
+        for msg in msgs {
+            let err = handle(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+            assert_eq!(err, ReflectError::OwnershipRenounced);
+        }
+    }
+
+    #[test]
+    fn renounce_ownership_requires_owner() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
+
+        let msg = InitMsg { callback_id: None };
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("random", &[]);
+        let err = handle(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            HandleMsg::RenounceOwnership {},
+        )
+        .unwrap_err();
+        match err {
+            ReflectError::NotCurrentOwner { .. } => {}
+            e => panic!("Unexpected error: {:?}", e),
+        }
+
+        let state = config_read(&deps.storage).load().unwrap();
+        assert!(!state.renounced);
+    }
+
+    #[test]
+    fn init_sets_current_state_version() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
+
+        let msg = InitMsg { callback_id: None };
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let state = config_read(&deps.storage).load().unwrap();
+        assert_eq!(state.version, STATE_VERSION);
+    }
+
+    #[test]
+    fn migrate_upgrades_old_state() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
+
+        // state as stored by code before versioning was introduced
+        let owner = deps
+            .api
+            .canonical_address(&HumanAddr::from("creator"))
+            .unwrap();
+        let old_state = format!(r#"{{"owner":"{}"}}"#, owner.0.to_base64());
+        deps.storage
+            .set(&to_length_prefixed(b"config"), old_state.as_bytes());
+
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        assert_eq!(0, res.messages.len());
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "migrate"),
+                attr("from_version", "0"),
+                attr("to_version", STATE_VERSION),
+            ]
+        );
+
+        let state = config_read(&deps.storage).load().unwrap();
+        assert_eq!(
+            state,
+            State {
+                owner,
+                paused: false,
+                renounced: false,
+                version: STATE_VERSION,
+                reject_self_calls: false,
+                verify_validators: false,
+                validate_denoms: false,
+            }
+        );
+
+        // the owner can still use the contract
+        let msg = HandleMsg::ReflectMsg {
+            msgs: vec![BankMsg::Send {
+                to_address: HumanAddr::from("friend"),
+                amount: coins(1, "token"),
+            }
+            .into()],
+        };
+        handle(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+    }
+
+    #[test]
+    fn migrate_rejects_future_state_version() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
+
+        let msg = InitMsg { callback_id: None };
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // state as stored by newer code
+        config(&mut deps.storage)
+            .update(|mut state| -> StdResult<_> {
+                state.version = STATE_VERSION + 1;
+                Ok(state)
+            })
+            .unwrap();
+
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+        assert_eq!(
+            err,
+            ReflectError::UnsupportedStateVersion {
+                version: STATE_VERSION + 1,
+                supported: STATE_VERSION,
+            }
+        );
+
+        // all other state access fails the same way
+        let msg = HandleMsg::SetPaused { paused: true };
+        let err = handle(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err();
+        match err {
+            ReflectError::UnsupportedStateVersion { .. } => {}
+            e => panic!("Unexpected error: {:?}", e),
+        }
+        let err = query(deps.as_ref(), mock_env(), QueryMsg::Owner {}).unwrap_err();
+        match err {
+            ReflectError::UnsupportedStateVersion { .. } => {}
+            e => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn corrupt_owner_is_rejected() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
+
+        let msg = InitMsg { callback_id: None };
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // empty owner
+        deps.storage.set(
+            &to_length_prefixed(b"config"),
+            br#"{"owner":"","paused":false,"renounced":false,"version":1}"#,
+        );
+        let err = query(deps.as_ref(), mock_env(), QueryMsg::Owner {}).unwrap_err();
+        assert_eq!(err, ReflectError::InvalidOwnerLength { length: 0 });
+
+        // owner much longer than any address
+        let state = State {
+            owner: CanonicalAddr(Binary(vec![0xAB; MAX_OWNER_LENGTH + 1])),
+            paused: false,
+            renounced: false,
+            version: STATE_VERSION,
+            reject_self_calls: false,
+            verify_validators: false,
+            validate_denoms: false,
+        };
+        config(&mut deps.storage).save(&state).unwrap();
+        let err = query(deps.as_ref(), mock_env(), QueryMsg::Owner {}).unwrap_err();
+        assert_eq!(
+            err,
+            ReflectError::InvalidOwnerLength {
+                length: MAX_OWNER_LENGTH + 1
+            }
+        );
+        let msg = HandleMsg::SetPaused { paused: true };
+        let err = handle(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err();
+        assert_eq!(
+            err,
+            ReflectError::InvalidOwnerLength {
+                length: MAX_OWNER_LENGTH + 1
+            }
+        );
+    }
+
+    #[test]
+    fn reply_stores_result_by_id() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
+
+        let msg = InitMsg { callback_id: None };
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let ok = Reply {
+            id: 123,
+            result: ContractResult::Ok(SubMsgExecutionResponse {
+                events: vec![Event {
+                    kind: "message".to_string(),
+                    attributes: vec![attr("signer", "caller-addr")],
+                }],
+                data: Some(Binary::from(b"foobar")),
+            }),
+        };
+        let res = reply(deps.as_mut(), mock_env(), ok.clone()).unwrap();
+        assert_eq!(0, res.messages.len());
+        assert_eq!(res.attributes, vec![attr("action", "reply")]);
+        assert_eq!(res.data, Some(Binary::from(b"foobar")));
+
+        let failed = Reply {
+            id: 456,
+            result: ContractResult::Err("insufficient funds".to_string()),
+        };
+        let res = reply(deps.as_mut(), mock_env(), failed.clone()).unwrap();
+        assert_eq!(res.attributes, vec![attr("action", "reply")]);
+        assert_eq!(res.data, None);
+
+        let stored = replies_read(&deps.storage)
+            .load(&123u64.to_be_bytes())
+            .unwrap();
+        assert_eq!(stored, ok);
+        let stored = replies_read(&deps.storage)
+            .load(&456u64.to_be_bytes())
+            .unwrap();
+        assert_eq!(stored, failed);
+    }
+
+    #[test]
+    fn capitalized_query_works() {
+        let deps = mock_dependencies_with_custom_querier(&[]);
+
+        let msg = QueryMsg::Capitalized {
+            text: "demo one".to_string(),
+        };
+        let response = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: CapitalizedResponse = from_binary(&response).unwrap();
+        assert_eq!(value.text, "DEMO ONE");
+    }
+
+    #[test]
+    fn capitalized_batch_query_works() {
+        let deps = mock_dependencies_with_custom_querier(&[]);
+
+        let msg = QueryMsg::CapitalizedBatch {
+            texts: vec![
+                "demo one".to_string(),
+                "Second".to_string(),
+                "3rd text".to_string(),
+            ],
+        };
+        let response = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: CapitalizedBatchResponse = from_binary(&response).unwrap();
+        assert_eq!(value.texts, vec!["DEMO ONE", "SECOND", "3RD TEXT"]);
+
+        // empty batch
+        let msg = QueryMsg::CapitalizedBatch { texts: vec![] };
+        let response = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: CapitalizedBatchResponse = from_binary(&response).unwrap();
+        assert_eq!(value.texts, Vec::<String>::new());
+    }
+
+    #[test]
+    fn chain_query_works() {
+        let deps = mock_dependencies_with_custom_querier(&coins(123, "ucosm"));
+
+        // with bank query
+        let msg = QueryMsg::Chain {
+            request: BankQuery::AllBalances {
+                address: HumanAddr::from(MOCK_CONTRACT_ADDR),
+            }
+            .into(),
+        };
+        let response = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let outer: ChainResponse = from_binary(&response).unwrap();
+        let inner: AllBalanceResponse = from_binary(&outer.data).unwrap();
+        assert_eq!(inner.amount, coins(123, "ucosm"));
+
+        // with custom query
+        let msg = QueryMsg::Chain {
+            request: SpecialQuery::Ping { nonce: Some(7) }.into(),
+        };
+        let response = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let outer: ChainResponse = from_binary(&response).unwrap();
+        let inner: SpecialResponse = from_binary(&outer.data).unwrap();
+        assert_eq!(inner.msg, "pong");
+        assert_eq!(inner.nonce, Some(7));
+    }
+
+    #[test]
+    fn chain_query_reports_missing_contract() {
+        let deps = mock_dependencies_with_custom_querier(&[]);
+
+        let msg = QueryMsg::Chain {
+            request: WasmQuery::Smart {
+                contract_addr: HumanAddr::from("nowhere"),
+                msg: Binary::from(b"{}".to_vec()),
+            }
+            .into(),
+        };
+        match query(deps.as_ref(), mock_env(), msg).unwrap_err() {
+            ReflectError::NoSuchContract { addr } => assert_eq!(addr, HumanAddr::from("nowhere")),
+            e => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn chain_query_random_is_seeded_by_height() {
+        let random = |height: u64| -> Binary {
+            let deps = mock_dependencies_at_height(height, &[]);
+            let msg = QueryMsg::Chain {
+                request: SpecialQuery::Random { count: 32 }.into(),
+            };
+            let response = query(deps.as_ref(), mock_env_at_height(height), msg).unwrap();
+            let outer: ChainResponse = from_binary(&response).unwrap();
+            let inner: RandomResponse = from_binary(&outer.data).unwrap();
+            inner.bytes
+        };
+
+        assert_eq!(random(100).len(), 32);
+        assert_eq!(random(100), random(100));
+        assert_ne!(random(100), random(101));
+
+        // typed chain query decodes the random response
+        let deps = mock_dependencies_at_height(100, &[]);
+        let msg = QueryMsg::ChainTyped {
+            request: SpecialQuery::Random { count: 32 }.into(),
+        };
+        let response = query(deps.as_ref(), mock_env_at_height(100), msg).unwrap();
+        let value: ChainTypedResponse = from_binary(&response).unwrap();
+        assert_eq!(
+            value,
+            ChainTypedResponse::Random(RandomResponse { bytes: random(100) })
+        );
+    }
+
+    #[test]
+    fn chain_query_uses_swapped_querier() {
+        let alice = HumanAddr::from("alice");
+        let mut deps = mock_dependencies_with_balances(&[(&alice, &coins(123, "ucosm"))]);
+
+        let msg = InitMsg { callback_id: None };
+        let info = mock_info("creator", &[]);
+        let _res = init(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = QueryMsg::Chain {
+            request: BankQuery::AllBalances {
+                address: alice.clone(),
+            }
+            .into(),
+        };
+        let response = query(deps.as_ref(), mock_env(), msg.clone()).unwrap();
+        let outer: ChainResponse = from_binary(&response).unwrap();
+        let inner: AllBalanceResponse = from_binary(&outer.data).unwrap();
+        assert_eq!(inner.amount, coins(123, "ucosm"));
+
+        // re-seed the balances, keeping the state
+        let querier = mock_dependencies_with_balances(&[(&alice, &coins(5, "ustake"))]).querier;
+        let deps = deps.with_querier(querier);
+
+        let response = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let outer: ChainResponse = from_binary(&response).unwrap();
+        let inner: AllBalanceResponse = from_binary(&outer.data).unwrap();
+        assert_eq!(inner.amount, coins(5, "ustake"));
+        let value = query_owner(deps.as_ref(), mock_env()).unwrap();
+        assert_eq!(value.owner, "creator");
+    }
+
+    #[test]
+    fn chain_query_works_for_multiple_addresses() {
+        let alice = HumanAddr::from("alice");
+        let bob = HumanAddr::from("bob");
+        let deps = mock_dependencies_with_balances(&[
+            (&alice, &coins(123, "ucosm")),
+            (&bob, &[coin(456, "ustake"), coin(7, "ucosm")]),
+        ]);
+
+        let msg = QueryMsg::Chain {
+            request: BankQuery::AllBalances {
+                address: alice.clone(),
+            }
+            .into(),
+        };
+        let response = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let outer: ChainResponse = from_binary(&response).unwrap();
+        let inner: AllBalanceResponse = from_binary(&outer.data).unwrap();
+        assert_eq!(inner.amount, coins(123, "ucosm"));
+
+        let msg = QueryMsg::Chain {
+            request: BankQuery::Balance {
+                address: bob.clone(),
+                denom: "ustake".to_string(),
+            }
+            .into(),
+        };
+        let response = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let outer: ChainResponse = from_binary(&response).unwrap();
+        let inner: BalanceResponse = from_binary(&outer.data).unwrap();
+        assert_eq!(inner.amount, coin(456, "ustake"));
+
+        // the contract itself has no balance unless explicitly set
+        let msg = QueryMsg::Chain {
+            request: BankQuery::AllBalances {
+                address: HumanAddr::from(MOCK_CONTRACT_ADDR),
+            }
+            .into(),
+        };
+        let response = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let outer: ChainResponse = from_binary(&response).unwrap();
+        let inner: AllBalanceResponse = from_binary(&outer.data).unwrap();
+        assert_eq!(inner.amount, vec![]);
+    }
+
+    #[test]
+    fn chain_query_delegation_works() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
+        let delegation = FullDelegation {
+            delegator: HumanAddr::from(MOCK_CONTRACT_ADDR),
+            validator: HumanAddr::from("validator"),
+            amount: coin(100, "ustake"),
+            can_redelegate: coin(40, "ustake"),
+            accumulated_rewards: coins(5, "ustake"),
+        };
+        deps.querier
+            .update_staking("ustake", &[], &[delegation.clone()]);
+
+        // existing delegation
+        let msg = QueryMsg::Chain {
+            request: StakingQuery::Delegation {
+                delegator: HumanAddr::from(MOCK_CONTRACT_ADDR),
+                validator: HumanAddr::from("validator"),
+            }
+            .into(),
+        };
+        let response = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let outer: ChainResponse = from_binary(&response).unwrap();
+        let inner: DelegationResponse = from_binary(&outer.data).unwrap();
+        let full = inner.delegation.unwrap();
+        assert_eq!(full.amount, coin(100, "ustake"));
+        assert_eq!(full.can_redelegate, coin(40, "ustake"));
+        assert_eq!(full.accumulated_rewards, coins(5, "ustake"));
+        assert_eq!(full, delegation);
+
+        // same result via the typed helper
+        let typed = deps
+            .as_ref()
+            .querier
+            .query_delegation(MOCK_CONTRACT_ADDR, "validator")
+            .unwrap();
+        assert_eq!(typed, Some(delegation));
+
+        // missing delegation
+        let msg = QueryMsg::Chain {
+            request: StakingQuery::Delegation {
+                delegator: HumanAddr::from(MOCK_CONTRACT_ADDR),
+                validator: HumanAddr::from("other"),
+            }
+            .into(),
+        };
+        let response = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let outer: ChainResponse = from_binary(&response).unwrap();
+        let inner: DelegationResponse = from_binary(&outer.data).unwrap();
+        assert_eq!(inner.delegation, None);
+    }
+
+    #[test]
+    fn chain_typed_query_works() {
+        let deps = mock_dependencies_with_custom_querier(&coins(123, "ucosm"));
+
+        // with bank query
+        let msg = QueryMsg::ChainTyped {
+            request: BankQuery::AllBalances {
+                address: HumanAddr::from(MOCK_CONTRACT_ADDR),
+            }
+            .into(),
+        };
+        let response = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: ChainTypedResponse = from_binary(&response).unwrap();
+        assert_eq!(
+            value,
+            ChainTypedResponse::AllBalances(AllBalanceResponse {
+                amount: coins(123, "ucosm")
+            })
+        );
+
+        let msg = QueryMsg::ChainTyped {
+            request: BankQuery::Balance {
+                address: HumanAddr::from(MOCK_CONTRACT_ADDR),
+                denom: "ucosm".to_string(),
+            }
+            .into(),
+        };
+        let response = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: ChainTypedResponse = from_binary(&response).unwrap();
+        assert_eq!(
+            value,
+            ChainTypedResponse::Balance(BalanceResponse {
+                amount: coin(123, "ucosm")
+            })
+        );
+
+        // with custom query
+        let msg = QueryMsg::ChainTyped {
+            request: SpecialQuery::Ping { nonce: None }.into(),
+        };
+        let response = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: ChainTypedResponse = from_binary(&response).unwrap();
+        assert_eq!(
+            value,
+            ChainTypedResponse::Special(SpecialResponse {
+                msg: "pong".to_string(),
+                nonce: None,
+            })
+        );
+    }
+
+    #[test]
+    fn chain_typed_query_rejects_unknown_kinds() {
+        let deps = mock_dependencies_with_custom_querier(&[]);
+
+        let msg = QueryMsg::ChainTyped {
+            request: wasm_query::raw("other", b"config"),
+        };
+        let err = query(deps.as_ref(), mock_env(), msg).unwrap_err();
+        match err {
+            ReflectError::Std(StdError::GenericErr { msg, .. }) => {
+                assert!(msg.contains("Unsupported request kind"))
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn chain_smart_query_passes_json_through() {
+        /// Answers smart queries to "other" with a fixed JSON result
+        struct SmartQuerier {}
+
+        impl Querier for SmartQuerier {
+            fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+                let request: QueryRequest<SpecialQuery> = from_slice(bin_request).unwrap();
+                match request {
+                    QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg })
+                        if contract_addr == "other" && msg.as_slice() == br#"{"config":{}}"# =>
+                    {
+                        SystemResult::Ok(ContractResult::Ok(Binary::from(
+                            br#"{"owner":"alice","count":7}"#,
+                        )))
+                    }
+                    _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                        kind: "test".to_string(),
+                    }),
+                }
+            }
+        }
+
+        let storage = MockStorage::default();
+        let api = MockApi::default();
+        let querier = SmartQuerier {};
+        let deps = Deps {
+            storage: &storage,
+            api: &api,
+            querier: QuerierWrapper::new(&querier),
+        };
+
+        let msg = QueryMsg::ChainSmart {
+            contract_addr: HumanAddr::from("other"),
+            msg: Binary::from(br#"{"config":{}}"#),
+        };
+        let response = query(deps, mock_env(), msg).unwrap();
+        assert_eq!(response.as_slice(), br#"{"owner":"alice","count":7}"#);
+
+        // a single decoding step is enough
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Config {
+            owner: HumanAddr,
+            count: u32,
+        }
+        let config: Config = from_binary(&response).unwrap();
+        assert_eq!(
+            config,
+            Config {
+                owner: HumanAddr::from("alice"),
+                count: 7
+            }
+        );
+
+        // errors of the queried contract are reported
+        let msg = QueryMsg::ChainSmart {
+            contract_addr: HumanAddr::from("unknown"),
+            msg: Binary::from(br#"{"config":{}}"#),
+        };
+        let err = query(deps, mock_env(), msg).unwrap_err();
+        match err {
+            ReflectError::Std(StdError::GenericErr { msg, .. }) => {
+                assert!(msg.starts_with("Querier system error"))
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn raw_chunked_query_works() {
+        /// Answers raw queries for key "big" of contract "other"
+        struct RawQuerier {
+            value: Vec<u8>,
+        }
+
+        impl Querier for RawQuerier {
+            fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+                let request: QueryRequest<SpecialQuery> = from_slice(bin_request).unwrap();
+                match request {
+                    QueryRequest::Wasm(WasmQuery::Raw { contract_addr, key })
+                        if contract_addr == "other" && key.as_slice() == b"big" =>
+                    {
+                        SystemResult::Ok(ContractResult::Ok(Binary::from(self.value.clone())))
+                    }
+                    _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                        kind: "test".to_string(),
+                    }),
+                }
+            }
+        }
+
+        let value: Vec<u8> = (0..10_000u32).map(|i| (i % 251) as u8).collect();
+        let storage = MockStorage::default();
+        let api = MockApi::default();
+        let querier = RawQuerier {
+            value: value.clone(),
+        };
+        let deps = Deps {
+            storage: &storage,
+            api: &api,
+            querier: QuerierWrapper::new(&querier),
+        };
+
+        let chunk = |offset: u32, limit: u32| -> RawChunkedResponse {
+            let msg = QueryMsg::RawChunked {
+                contract: HumanAddr::from("other"),
+                key: Binary::from(b"big"),
+                offset,
+                limit,
+            };
+            from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap()
+        };
+
+        // read the value in two chunks
+        let first = chunk(0, 6_000);
+        assert_eq!(first.total_len, 10_000);
+        assert_eq!(first.data.as_slice(), &value[..6_000]);
+        let second = chunk(6_000, 6_000);
+        assert_eq!(second.total_len, 10_000);
+        assert_eq!(second.data.as_slice(), &value[6_000..]);
+
+        // reading beyond the end returns no data
+        let end = chunk(10_000, 6_000);
+        assert_eq!(end.total_len, 10_000);
+        assert_eq!(end.data.as_slice(), b"");
+        let end = chunk(u32::MAX, u32::MAX);
+        assert_eq!(end.data.as_slice(), b"");
+    }
+
+    #[test]
+    fn remote_state_query_works() {
+        /// Answers raw queries for the state of contract "other"
+        struct StateQuerier {
+            state: State,
+        }
+
+        impl Querier for StateQuerier {
+            fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+                let request: QueryRequest<SpecialQuery> = from_slice(bin_request).unwrap();
+                let value = match request {
+                    QueryRequest::Wasm(WasmQuery::Raw { contract_addr, key })
+                        if contract_addr == "other" && key.as_slice() == config_raw_key() =>
+                    {
+                        to_vec(&self.state).unwrap()
+                    }
+                    _ => vec![],
+                };
+                SystemResult::Ok(ContractResult::Ok(Binary::from(value)))
+            }
+        }
+
+        let storage = MockStorage::default();
+        let api = MockApi::default();
+        let state = State {
+            owner: api.canonical_address(&HumanAddr::from("creator")).unwrap(),
+            paused: true,
+            renounced: false,
+            version: STATE_VERSION,
+            reject_self_calls: false,
+            verify_validators: false,
+            validate_denoms: false,
+        };
+        let querier = StateQuerier {
+            state: state.clone(),
+        };
+        let deps = Deps {
+            storage: &storage,
+            api: &api,
+            querier: QuerierWrapper::new(&querier),
+        };
+
+        let msg = QueryMsg::RemoteState {
+            contract: HumanAddr::from("other"),
+        };
+        let response = query(deps, mock_env(), msg).unwrap();
+        let value: RemoteStateResponse = from_binary(&response).unwrap();
+        assert_eq!(value.state, Some(state));
+
+        // no state stored in the contract
+        let msg = QueryMsg::RemoteState {
+            contract: HumanAddr::from("empty"),
+        };
+        let response = query(deps, mock_env(), msg).unwrap();
+        let value: RemoteStateResponse = from_binary(&response).unwrap();
+        assert_eq!(value.state, None);
+    }
+
+    #[test]
+    fn introspect_query_works() {
+        let deps = mock_dependencies_with_custom_querier(&[]);
+
+        let response = query(deps.as_ref(), mock_env(), QueryMsg::Introspect {}).unwrap();
+        let value: IntrospectResponse = from_binary(&response).unwrap();
+        assert_eq!(
+            value.message_families,
+            vec!["bank", "custom", "staking", "stargate", "ibc", "wasm"]
+        );
+        assert_eq!(
+            value.features,
+            vec![
+                "paused",
+                "reflect-once",
+                "reject-self-calls",
+                "renounce-ownership",
+                "validate-denoms",
+                "verify-validators"
+            ]
+        );
+
+        // every compiled message family is advertised
+        let msgs: Vec<CosmosMsg<CustomMsg>> = vec![
+            BankMsg::Send {
+                to_address: HumanAddr::from("friend"),
+                amount: coins(1, "token"),
+            }
+            .into(),
+            CustomMsg::Raw(Binary(b"{}".to_vec())).into(),
+            StakingMsg::Undelegate {
+                validator: HumanAddr::from("validator"),
+                amount: coin(100, "ustake"),
+            }
+            .into(),
+            CosmosMsg::Stargate {
+                type_url: "/cosmos.gov.v1beta1.MsgVote".to_string(),
+                value: Binary::from(b"vote"),
+            },
+            IbcMsg::Transfer {
+                channel_id: "channel-0".to_string(),
+                to_address: HumanAddr::from("remote"),
+                amount: coin(1, "token"),
+                timeout_block: None,
+                timeout_timestamp: Some(1),
+            }
+            .into(),
+            WasmMsg::Execute {
+                contract_addr: HumanAddr::from("other"),
+                msg: Binary::from(b"{}"),
+                send: vec![],
+            }
+            .into(),
+        ];
+        let families: Vec<&str> = msgs
+            .iter()
+            .map(|msg| message_family(msg).unwrap())
+            .collect();
+        assert_eq!(families, value.message_families);
+    }
+
+    #[test]
+    fn chain_query_rejects_oversized_requests() {
+        let deps = mock_dependencies_with_custom_querier(&[]);
+
+        let msg = QueryMsg::Chain {
+            request: SpecialQuery::Capitalized {
+                text: "a".repeat(MAX_CHAIN_REQUEST_SIZE),
+            }
+            .into(),
+        };
+        let err = query(deps.as_ref(), mock_env(), msg).unwrap_err();
+        match err {
+            ReflectError::RequestTooLarge { size, max } => {
+                assert!(size > MAX_CHAIN_REQUEST_SIZE);
+                assert_eq!(max, MAX_CHAIN_REQUEST_SIZE);
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+
+        // slightly smaller requests are forwarded
+        let msg = QueryMsg::Chain {
+            request: SpecialQuery::Capitalized {
+                text: "a".repeat(MAX_CHAIN_REQUEST_SIZE - 100),
+            }
+            .into(),
+        };
+        query(deps.as_ref(), mock_env(), msg).unwrap();
+    }
+
+    #[test]
+    fn check_request_size_works() {
+        check_request_size(b"", 0).unwrap();
+        check_request_size(b"12345", 5).unwrap();
+        let err = check_request_size(b"123456", 5).unwrap_err();
+        assert_eq!(err, ReflectError::RequestTooLarge { size: 6, max: 5 });
+        assert_eq!(
+            err.to_string(),
+            "Request too large: 6 bytes exceeds the limit of 5 bytes"
+        );
     }
 }
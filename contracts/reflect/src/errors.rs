@@ -1,9 +1,14 @@
-use cosmwasm_std::{CanonicalAddr, StdError};
+use cosmwasm_std::{CanonicalAddr, HumanAddr, StdError};
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
 pub enum ReflectError {
-    #[error("{0}")]
+    /// Every `StdError` converts into this variant using `?`, with the original error kept intact.
+    /// This covers storage, address conversion, coin validation and query errors.
+    /// Only serialization in `init` is reported as `Serialization` to add context.
+    /// Display and source are forwarded to the `StdError`, such that its message is not
+    /// repeated when walking the source chain.
+    #[error(transparent)]
     // let thiserror implement From<StdError> for you
     Std(#[from] StdError),
     // this is whatever we want
@@ -14,4 +19,64 @@ pub enum ReflectError {
     },
     #[error("Messages empty. Must reflect at least one message")]
     MessagesEmpty,
+    /// A single message of the batch was rejected. `index` is its position in the batch.
+    ///
+    /// This is only returned for invalid denoms (if `validate_denoms` is set) and, in
+    /// `SimulateReflect`, for messages that cannot be serialized.
+    #[error("Invalid message at index {index}: {reason}")]
+    InvalidMessageAt { index: usize, reason: String },
+    #[error("Funds were sent but there is no bank send message to forward them with")]
+    FundsWithoutSend,
+    /// A message of the batch executes this contract, which is rejected if `reject_self_calls` is set
+    #[error("Message at index {index} calls this contract")]
+    SelfCall { index: usize },
+    /// A message of the batch delegates to a validator the chain does not know,
+    /// which is rejected if `verify_validators` is set
+    #[error("Message at index {index} delegates to unknown validator {validator}")]
+    UnknownValidator { index: usize, validator: HumanAddr },
+    #[error("Contract is paused")]
+    Paused,
+    #[error("Ownership has been renounced")]
+    OwnershipRenounced,
+    #[error("Request too large: {size} bytes exceeds the limit of {max} bytes")]
+    RequestTooLarge { size: usize, max: usize },
+    #[error("Unsupported state version {version}, this code supports up to version {supported}")]
+    UnsupportedStateVersion { version: u16, supported: u16 },
+    /// The stored state could be decoded but contains an owner address of impossible length
+    #[error("Corrupt state: owner address has invalid length {length}")]
+    InvalidOwnerLength { length: usize },
+    /// A chain query was sent to a contract that does not exist
+    #[error("No such contract: {addr}")]
+    NoSuchContract { addr: HumanAddr },
+    #[error("Serialization failed in {context}: {msg}")]
+    Serialization { context: String, msg: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::State;
+    use cosmwasm_std::from_slice;
+    use std::error::Error;
+
+    #[test]
+    fn source_chain_can_be_walked() {
+        let error = ReflectError::from(from_slice::<State>(b"{").unwrap_err());
+
+        // the StdError is transparent, so the first source is the underlying parse error
+        // and each message of the chain appears once
+        let source = error.source().expect("must have a source");
+        assert_eq!(
+            error.to_string(),
+            format!("Error parsing into type reflect::state::State: {}", source)
+        );
+        assert!(source.source().is_none());
+
+        let error = ReflectError::from(StdError::not_found("reflect::state::State"));
+        assert_eq!(error.to_string(), "reflect::state::State not found");
+        assert!(error.source().is_none());
+
+        let error = ReflectError::MessagesEmpty;
+        assert!(error.source().is_none());
+    }
 }
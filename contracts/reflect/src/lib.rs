@@ -7,4 +7,4 @@ pub mod state;
 pub mod testing;
 
 #[cfg(target_arch = "wasm32")]
-cosmwasm_std::create_entry_points!(contract);
+cosmwasm_std::create_entry_points_with_migration!(contract);
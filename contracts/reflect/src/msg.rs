@@ -1,9 +1,16 @@
 #![allow(clippy::field_reassign_with_default)] // see https://github.com/CosmWasm/cosmwasm/issues/685
 
 use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
+use serde::de::{self, IgnoredAny, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
 
-use cosmwasm_std::{Binary, CosmosMsg, CustomQuery, HumanAddr, QueryRequest};
+use cosmwasm_std::{
+    AllBalanceResponse, BalanceResponse, Binary, Coin, CosmosMsg, CustomQuery, HumanAddr,
+    QueryRequest,
+};
+
+use crate::state::State;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InitMsg {
@@ -24,11 +31,59 @@ pub enum CallbackMsg {
     },
 }
 
+/// Upgrades the stored state to the current `STATE_VERSION`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum HandleMsg {
-    ReflectMsg { msgs: Vec<CosmosMsg<CustomMsg>> },
-    ChangeOwner { owner: HumanAddr },
+    ReflectMsg {
+        msgs: Vec<CosmosMsg<CustomMsg>>,
+    },
+    /// Like `ReflectMsg`, but executes at most once per idempotency `key`.
+    /// The response data is the JSON of the reflected messages. Subsequent calls with the
+    /// same key return the data of the first execution without emitting any messages.
+    ReflectMsgOnce {
+        key: String,
+        msgs: Vec<CosmosMsg<CustomMsg>>,
+    },
+    /// Like `ReflectMsg`, but forwards the funds sent along with this message by adding them
+    /// to the first `BankMsg::Send` in `msgs`. Fails if funds are sent but there is no such message.
+    ReflectMsgWithFunds {
+        msgs: Vec<CosmosMsg<CustomMsg>>,
+    },
+    ChangeOwner {
+        owner: HumanAddr,
+    },
+    /// Pauses or unpauses reflecting messages and sending tokens via `MaybeSend`.
+    /// Only the owner can do this.
+    SetPaused {
+        paused: bool,
+    },
+    /// Enables or disables rejecting reflected messages that execute this contract,
+    /// which protects against reentrancy. Only the owner can do this.
+    SetRejectSelfCalls {
+        reject: bool,
+    },
+    /// Enables or disables checking that delegations go to validators known to the chain.
+    /// Only the owner can do this.
+    SetVerifyValidators {
+        verify: bool,
+    },
+    /// Enables or disables rejecting bank messages with denoms that do not follow the
+    /// Cosmos SDK rules. Only the owner can do this.
+    SetValidateDenoms {
+        validate: bool,
+    },
+    /// Permanently gives up ownership, which makes the contract immutable.
+    /// Afterwards all owner-only actions fail.
+    RenounceOwnership {},
+    /// Sends `amount` to `recipient` if it is set and non-zero. Otherwise this is a no-op.
+    MaybeSend {
+        recipient: HumanAddr,
+        amount: Option<Coin>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -39,15 +94,53 @@ pub enum QueryMsg {
     Capitalized {
         text: String,
     },
+    /// Like `Capitalized`, but for multiple texts at once.
+    /// The results are returned in the same order as `texts`.
+    CapitalizedBatch {
+        texts: Vec<String>,
+    },
     /// Queries the blockchain and returns the result untouched
     Chain {
         request: QueryRequest<SpecialQuery>,
     },
+    /// Like `Chain`, but decodes the result for known request kinds
+    /// (bank balances and custom queries) and returns it typed
+    ChainTyped {
+        request: QueryRequest<SpecialQuery>,
+    },
+    /// Sends a smart query to another contract and returns its JSON result as is,
+    /// i.e. not wrapped in a `Binary` like `Chain` does. This saves clients from decoding twice.
+    ChainSmart {
+        contract_addr: HumanAddr,
+        msg: Binary,
+    },
     /// Queries another contract and returns the data
     Raw {
         contract: HumanAddr,
         key: Binary,
     },
+    /// Like `Raw`, but only returns up to `limit` bytes of the data starting at `offset`.
+    /// This allows clients to page through large values.
+    ///
+    /// Only the response size is bounded. The complete value is still loaded from the other
+    /// contract, so the query costs as much gas as `Raw`.
+    RawChunked {
+        contract: HumanAddr,
+        key: Binary,
+        offset: u32,
+        limit: u32,
+    },
+    /// Checks a batch of messages like `HandleMsg::ReflectMsg` would, without executing it.
+    /// The owner check is skipped. Returns a report of all problems found.
+    SimulateReflect {
+        msgs: Vec<CosmosMsg<CustomMsg>>,
+    },
+    /// Reads the state of another reflect contract with a raw query
+    RemoteState {
+        contract: HumanAddr,
+    },
+    /// Returns the message families and optional features this contract supports
+    Introspect {},
 }
 
 // We define a custom struct for each query response
@@ -55,6 +148,12 @@ pub enum QueryMsg {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct OwnerResponse {
     pub owner: HumanAddr,
+    /// The block height at which the owner was queried, to let clients know how fresh the data is
+    #[serde(default)]
+    pub block_height: Option<u64>,
+    /// True if the owner renounced ownership. In this case the owner cannot perform any actions anymore.
+    #[serde(default)]
+    pub renounced: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -63,12 +162,62 @@ pub struct CapitalizedResponse {
     pub text: String,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct CapitalizedBatchResponse {
+    pub texts: Vec<String>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct ChainResponse {
     pub data: Binary,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChainTypedResponse {
+    Balance(BalanceResponse),
+    AllBalances(AllBalanceResponse),
+    Special(SpecialResponse),
+    Random(RandomResponse),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct IntrospectResponse {
+    /// The `CosmosMsg` variants that can be reflected, e.g. "bank" or "ibc"
+    pub message_families: Vec<String>,
+    /// Optional features implemented by this contract, e.g. "paused"
+    pub features: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct RawChunkedResponse {
+    /// The requested part of the value. This is empty if `offset` is at or
+    /// beyond the end of the value.
+    pub data: Binary,
+    /// The length of the complete value in bytes
+    pub total_len: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct SimulateReflectResponse {
+    /// True if reflecting the batch would succeed for the owner
+    pub valid: bool,
+    /// The reasons for rejecting the batch. This is empty if `valid` is true.
+    pub errors: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct RemoteStateResponse {
+    /// The state of the other contract. This is `None` if no state is stored there.
+    pub state: Option<State>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct RawResponse {
@@ -78,14 +227,105 @@ pub struct RawResponse {
     pub data: Binary,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[derive(Serialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 /// CustomMsg is an override of CosmosMsg::Custom to show this works and can be extended in the contract
 pub enum CustomMsg {
-    Debug(String),
+    /// A debug message tagged with a log level.
+    /// The legacy form `{"debug":"text"}` is still accepted and gets the level `info`.
+    Debug {
+        level: DebugLevel,
+        msg: String,
+    },
     Raw(Binary),
 }
 
+impl<'de> Deserialize<'de> for CustomMsg {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "snake_case")]
+        enum Repr {
+            Debug(DebugRepr),
+            Raw(Binary),
+        }
+
+        let msg = match Repr::deserialize(deserializer)? {
+            Repr::Debug(DebugRepr { level, msg }) => CustomMsg::Debug { level, msg },
+            Repr::Raw(data) => CustomMsg::Raw(data),
+        };
+        Ok(msg)
+    }
+}
+
+/// The content of `CustomMsg::Debug`, which is either `{"level":…,"msg":…}` or a legacy string.
+///
+/// This uses a hand-written visitor rather than `#[serde(untagged)]`, which buffers the input
+/// in a way that pulls float operations into the Wasm.
+struct DebugRepr {
+    level: DebugLevel,
+    msg: String,
+}
+
+impl<'de> Deserialize<'de> for DebugRepr {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(DebugReprVisitor)
+    }
+}
+
+struct DebugReprVisitor;
+
+impl<'de> Visitor<'de> for DebugReprVisitor {
+    type Value = DebugRepr;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a string or an object with level and msg")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        Ok(DebugRepr {
+            level: DebugLevel::Info,
+            msg: value.to_string(),
+        })
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut level: Option<DebugLevel> = None;
+        let mut msg: Option<String> = None;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "level" => {
+                    if level.is_some() {
+                        return Err(de::Error::duplicate_field("level"));
+                    }
+                    level = Some(map.next_value()?);
+                }
+                "msg" => {
+                    if msg.is_some() {
+                        return Err(de::Error::duplicate_field("msg"));
+                    }
+                    msg = Some(map.next_value()?);
+                }
+                // like derived implementations, ignore unknown fields
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(DebugRepr {
+            level: level.ok_or_else(|| de::Error::missing_field("level"))?,
+            msg: msg.ok_or_else(|| de::Error::missing_field("msg"))?,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DebugLevel {
+    Info,
+    Warn,
+    Error,
+}
+
 impl Into<CosmosMsg<CustomMsg>> for CustomMsg {
     fn into(self) -> CosmosMsg<CustomMsg> {
         CosmosMsg::Custom(self)
@@ -96,15 +336,103 @@ impl Into<CosmosMsg<CustomMsg>> for CustomMsg {
 #[serde(rename_all = "snake_case")]
 /// An implementation of QueryRequest::Custom to show this works and can be extended in the contract
 pub enum SpecialQuery {
-    Ping {},
-    Capitalized { text: String },
+    Ping {
+        /// An optional value that is echoed back in the response to correlate it with the request
+        nonce: Option<u64>,
+    },
+    Capitalized {
+        text: String,
+    },
+    /// Returns `count` random bytes as `RandomResponse`
+    Random {
+        count: u32,
+    },
 }
 
 impl CustomQuery for SpecialQuery {}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
-/// The response data for all `SpecialQuery`s
+/// The response data for `SpecialQuery::Ping` and `SpecialQuery::Capitalized`
 pub struct SpecialResponse {
     pub msg: String,
+    /// The nonce of a `Ping` request
+    pub nonce: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+/// The response data for `SpecialQuery::Random`
+pub struct RandomResponse {
+    pub bytes: Binary,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{from_slice, to_vec};
+
+    #[test]
+    fn custom_msg_debug_serializes_level() {
+        for (level, json) in &[
+            (DebugLevel::Info, r#"{"debug":{"level":"info","msg":"Hi"}}"#),
+            (DebugLevel::Warn, r#"{"debug":{"level":"warn","msg":"Hi"}}"#),
+            (
+                DebugLevel::Error,
+                r#"{"debug":{"level":"error","msg":"Hi"}}"#,
+            ),
+        ] {
+            let msg = CustomMsg::Debug {
+                level: *level,
+                msg: "Hi".to_string(),
+            };
+            let serialized = to_vec(&msg).unwrap();
+            assert_eq!(String::from_utf8(serialized.clone()).unwrap(), *json);
+            let deserialized: CustomMsg = from_slice(&serialized).unwrap();
+            assert_eq!(deserialized, msg);
+        }
+    }
+
+    #[test]
+    fn custom_msg_debug_deserializes_legacy_string() {
+        let msg: CustomMsg = from_slice(br#"{"debug":"Hi, Dad!"}"#).unwrap();
+        assert_eq!(
+            msg,
+            CustomMsg::Debug {
+                level: DebugLevel::Info,
+                msg: "Hi, Dad!".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn custom_msg_raw_still_works() {
+        let msg: CustomMsg = from_slice(br#"{"raw":"eyJmb28iOjEyM30="}"#).unwrap();
+        assert_eq!(msg, CustomMsg::Raw(Binary(b"{\"foo\":123}".to_vec())));
+
+        // unknown levels are rejected
+        from_slice::<CustomMsg>(br#"{"debug":{"level":"trace","msg":"Hi"}}"#).unwrap_err();
+    }
+
+    #[test]
+    fn custom_msg_debug_rejects_invalid_objects() {
+        // missing fields
+        from_slice::<CustomMsg>(br#"{"debug":{"msg":"Hi"}}"#).unwrap_err();
+        from_slice::<CustomMsg>(br#"{"debug":{"level":"info"}}"#).unwrap_err();
+        // duplicate fields
+        from_slice::<CustomMsg>(br#"{"debug":{"level":"info","msg":"Hi","msg":"Ho"}}"#)
+            .unwrap_err();
+        // neither string nor object
+        from_slice::<CustomMsg>(br#"{"debug":17}"#).unwrap_err();
+
+        // fields can be in any order
+        let msg: CustomMsg = from_slice(br#"{"debug":{"msg":"Hi","level":"warn"}}"#).unwrap();
+        assert_eq!(
+            msg,
+            CustomMsg::Debug {
+                level: DebugLevel::Warn,
+                msg: "Hi".to_string(),
+            }
+        );
+    }
 }
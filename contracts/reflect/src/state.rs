@@ -3,14 +3,48 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{CanonicalAddr, Storage};
-use cosmwasm_storage::{singleton, singleton_read, ReadonlySingleton, Singleton};
+use cosmwasm_std::{Binary, CanonicalAddr, Reply, Storage};
+use cosmwasm_storage::{
+    bucket, bucket_read, singleton, singleton_read, to_length_prefixed, Bucket, ReadonlyBucket,
+    ReadonlySingleton, Singleton,
+};
 
 const CONFIG_KEY: &[u8] = b"config";
+const RESULT_PREFIX: &[u8] = b"result";
+const EXECUTIONS_PREFIX: &[u8] = b"executions";
+
+/// The version of the `State` layout written by this code.
+/// Bump this whenever `State` changes and handle the upgrade in `migrate`.
+pub const STATE_VERSION: u16 = 4;
+
+/// The maximum length in bytes of a canonical owner address. Chains use 20 or 32 bytes,
+/// so anything longer (or an empty address) in `State` means the stored data is corrupt.
+pub const MAX_OWNER_LENGTH: usize = 64;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct State {
     pub owner: CanonicalAddr,
+    /// When set, reflecting messages and `MaybeSend` are blocked. Queries still work.
+    #[serde(default)]
+    pub paused: bool,
+    /// When set, ownership was renounced and owner-only actions always fail
+    #[serde(default)]
+    pub renounced: bool,
+    /// The layout version this state was written with (see `STATE_VERSION`).
+    /// State stored before versioning was introduced has version 0.
+    #[serde(default)]
+    pub version: u16,
+    /// When set, reflecting messages that execute this contract is rejected
+    #[serde(default)]
+    pub reject_self_calls: bool,
+    /// When set, delegations are only reflected to validators known to the chain.
+    /// This costs an extra query per reflect call containing a delegation.
+    #[serde(default)]
+    pub verify_validators: bool,
+    /// When set, bank messages with denoms that do not follow the Cosmos SDK rules are rejected.
+    /// This is off by default since chains can use other denom formats.
+    #[serde(default)]
+    pub validate_denoms: bool,
 }
 
 pub fn config(storage: &mut dyn Storage) -> Singleton<State> {
@@ -20,3 +54,114 @@ pub fn config(storage: &mut dyn Storage) -> Singleton<State> {
 pub fn config_read(storage: &dyn Storage) -> ReadonlySingleton<State> {
     singleton_read(storage, CONFIG_KEY)
 }
+
+/// The raw storage key of the state. Other contracts can use it to read the state with a raw query.
+pub fn config_raw_key() -> Vec<u8> {
+    to_length_prefixed(CONFIG_KEY)
+}
+
+/// Submessage results received in `reply`, stored by their id (as big endian bytes)
+pub fn replies(storage: &mut dyn Storage) -> Bucket<Reply> {
+    bucket(storage, RESULT_PREFIX)
+}
+
+pub fn replies_read(storage: &dyn Storage) -> ReadonlyBucket<Reply> {
+    bucket_read(storage, RESULT_PREFIX)
+}
+
+/// Response data of `ReflectMsgOnce` executions, stored by their idempotency key
+pub fn executions(storage: &mut dyn Storage) -> Bucket<Option<Binary>> {
+    bucket(storage, EXECUTIONS_PREFIX)
+}
+
+pub fn executions_read(storage: &dyn Storage) -> ReadonlyBucket<Option<Binary>> {
+    bucket_read(storage, EXECUTIONS_PREFIX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{from_slice, to_vec};
+
+    #[test]
+    fn state_serialization_is_stable() {
+        let state = State {
+            owner: CanonicalAddr(Binary::from([0, 187, 61, 11, 250, 0])),
+            paused: false,
+            renounced: false,
+            version: 4,
+            reject_self_calls: true,
+            verify_validators: true,
+            validate_denoms: true,
+        };
+        let serialized = to_vec(&state).unwrap();
+        assert_eq!(
+            serialized,
+            br#"{"owner":"ALs9C/oA","paused":false,"renounced":false,"version":4,"reject_self_calls":true,"verify_validators":true,"validate_denoms":true}"#
+        );
+
+        // known good JSON from storage
+        let deserialized: State = from_slice(
+            br#"{"owner":"ALs9C/oA","paused":false,"renounced":false,"version":4,"reject_self_calls":true,"verify_validators":true,"validate_denoms":true}"#,
+        )
+        .unwrap();
+        assert_eq!(deserialized, state);
+
+        // state stored before `validate_denoms` was introduced
+        let deserialized: State = from_slice(
+            br#"{"owner":"ALs9C/oA","paused":false,"renounced":false,"version":3,"reject_self_calls":true,"verify_validators":true}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            deserialized,
+            State {
+                version: 3,
+                validate_denoms: false,
+                ..state.clone()
+            }
+        );
+
+        // state stored before `verify_validators` was introduced
+        let deserialized: State = from_slice(
+            br#"{"owner":"ALs9C/oA","paused":false,"renounced":false,"version":2,"reject_self_calls":true}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            deserialized,
+            State {
+                version: 2,
+                verify_validators: false,
+                validate_denoms: false,
+                ..state.clone()
+            }
+        );
+
+        // state stored before `reject_self_calls` was introduced
+        let deserialized: State =
+            from_slice(br#"{"owner":"ALs9C/oA","paused":false,"renounced":false,"version":1}"#)
+                .unwrap();
+        assert_eq!(
+            deserialized,
+            State {
+                version: 1,
+                reject_self_calls: false,
+                verify_validators: false,
+                validate_denoms: false,
+                ..state.clone()
+            }
+        );
+
+        // state stored before `paused`, `renounced` and `version` were introduced
+        let deserialized: State = from_slice(br#"{"owner":"ALs9C/oA"}"#).unwrap();
+        assert_eq!(
+            deserialized,
+            State {
+                version: 0,
+                reject_self_calls: false,
+                verify_validators: false,
+                validate_denoms: false,
+                ..state
+            }
+        );
+    }
+}
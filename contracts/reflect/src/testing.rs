@@ -1,6 +1,6 @@
-use crate::msg::{SpecialQuery, SpecialResponse};
+use crate::msg::{RandomResponse, SpecialQuery, SpecialResponse};
 
-use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::testing::{mock_env, MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{to_binary, Binary, Coin, ContractResult, HumanAddr, OwnedDeps, SystemResult};
 
 /// A drop-in replacement for cosmwasm_std::testing::mock_dependencies
@@ -9,9 +9,35 @@ pub fn mock_dependencies_with_custom_querier(
     contract_balance: &[Coin],
 ) -> OwnedDeps<MockStorage, MockApi, MockQuerier<SpecialQuery>> {
     let contract_addr = HumanAddr::from(MOCK_CONTRACT_ADDR);
+    mock_dependencies_with_balances(&[(&contract_addr, contract_balance)])
+}
+
+/// Like mock_dependencies_with_custom_querier but sets all balances provided
+/// (you must explicitly set the contract balance if desired)
+pub fn mock_dependencies_with_balances(
+    balances: &[(&HumanAddr, &[Coin])],
+) -> OwnedDeps<MockStorage, MockApi, MockQuerier<SpecialQuery>> {
+    mock_dependencies_for(balances, mock_env().block.height)
+}
+
+/// Like mock_dependencies_with_custom_querier but seeds `SpecialQuery::Random` with the
+/// given block height instead of the one of `mock_env()`. Use the same height as in the `Env`.
+pub fn mock_dependencies_at_height(
+    height: u64,
+    contract_balance: &[Coin],
+) -> OwnedDeps<MockStorage, MockApi, MockQuerier<SpecialQuery>> {
+    let contract_addr = HumanAddr::from(MOCK_CONTRACT_ADDR);
+    mock_dependencies_for(&[(&contract_addr, contract_balance)], height)
+}
+
+fn mock_dependencies_for(
+    balances: &[(&HumanAddr, &[Coin])],
+    height: u64,
+) -> OwnedDeps<MockStorage, MockApi, MockQuerier<SpecialQuery>> {
     let custom_querier: MockQuerier<SpecialQuery> =
-        MockQuerier::new(&[(&contract_addr, contract_balance)])
-            .with_custom_handler(|query| SystemResult::Ok(custom_query_execute(&query)));
+        MockQuerier::new(balances).with_custom_handler(move |query| {
+            SystemResult::Ok(custom_query_execute_at_height(query, height))
+        });
     OwnedDeps {
         storage: MockStorage::default(),
         api: MockApi::default(),
@@ -19,24 +45,68 @@ pub fn mock_dependencies_with_custom_querier(
     }
 }
 
+/// Executes a custom query, seeding `SpecialQuery::Random` with the block height of `mock_env()`
 pub fn custom_query_execute(query: &SpecialQuery) -> ContractResult<Binary> {
-    let msg = match query {
-        SpecialQuery::Ping {} => "pong".to_string(),
-        SpecialQuery::Capitalized { text } => text.to_uppercase(),
-    };
-    to_binary(&SpecialResponse { msg }).into()
+    custom_query_execute_at_height(query, mock_env().block.height)
+}
+
+pub fn custom_query_execute_at_height(query: &SpecialQuery, height: u64) -> ContractResult<Binary> {
+    match query {
+        SpecialQuery::Ping { nonce } => to_binary(&SpecialResponse {
+            msg: "pong".to_string(),
+            nonce: *nonce,
+        }),
+        SpecialQuery::Capitalized { text } => to_binary(&SpecialResponse {
+            msg: text.to_uppercase(),
+            nonce: None,
+        }),
+        SpecialQuery::Random { count } => to_binary(&RandomResponse {
+            bytes: Binary(mock_random_bytes(height, *count)),
+        }),
+    }
+    .into()
+}
+
+/// Deterministic pseudo random bytes (SplitMix64) for reproducible tests. Not suited for anything else.
+fn mock_random_bytes(seed: u64, count: u32) -> Vec<u8> {
+    let count = count as usize;
+    let mut state = seed;
+    let mut bytes = Vec::with_capacity(count + 8);
+    while bytes.len() < count {
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        bytes.extend_from_slice(&z.to_be_bytes());
+    }
+    bytes.truncate(count);
+    bytes
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use cosmwasm_std::{from_binary, QuerierWrapper, QueryRequest};
+    use cosmwasm_std::{from_binary, from_slice, QuerierWrapper, QueryRequest};
 
     #[test]
     fn custom_query_execute_ping() {
-        let res = custom_query_execute(&SpecialQuery::Ping {}).unwrap();
+        let res = custom_query_execute(&SpecialQuery::Ping { nonce: None }).unwrap();
         let response: SpecialResponse = from_binary(&res).unwrap();
         assert_eq!(response.msg, "pong");
+        assert_eq!(response.nonce, None);
+    }
+
+    #[test]
+    fn custom_query_execute_ping_echoes_nonce() {
+        let res = custom_query_execute(&SpecialQuery::Ping { nonce: Some(42) }).unwrap();
+        let response: SpecialResponse = from_binary(&res).unwrap();
+        assert_eq!(response.msg, "pong");
+        assert_eq!(response.nonce, Some(42));
+
+        // nonce can be omitted in JSON
+        let query: SpecialQuery = from_slice(br#"{"ping":{}}"#).unwrap();
+        assert_eq!(query, SpecialQuery::Ping { nonce: None });
     }
 
     #[test]
@@ -49,6 +119,25 @@ mod tests {
         assert_eq!(response.msg, "FOOBAR");
     }
 
+    #[test]
+    fn custom_query_execute_random() {
+        let random = |height: u64, count: u32| -> Binary {
+            let res =
+                custom_query_execute_at_height(&SpecialQuery::Random { count }, height).unwrap();
+            let response: RandomResponse = from_binary(&res).unwrap();
+            response.bytes
+        };
+
+        assert_eq!(random(100, 20).len(), 20);
+        assert_eq!(random(100, 0).len(), 0);
+        // the same height results in the same bytes
+        assert_eq!(random(100, 20), random(100, 20));
+        // a longer output starts with the shorter one
+        assert_eq!(random(100, 20).as_slice(), &random(100, 33)[..20]);
+        // different heights result in different bytes
+        assert_ne!(random(100, 20), random(101, 20));
+    }
+
     #[test]
     fn custom_querier() {
         let deps = mock_dependencies_with_custom_querier(&[]);
@@ -30,7 +30,8 @@ use cosmwasm_vm::{
 };
 
 use reflect::msg::{
-    CapitalizedResponse, CustomMsg, HandleMsg, InitMsg, OwnerResponse, QueryMsg, SpecialQuery,
+    CapitalizedResponse, CustomMsg, DebugLevel, HandleMsg, InitMsg, OwnerResponse, QueryMsg,
+    SpecialQuery,
 };
 use reflect::testing::custom_query_execute;
 
@@ -89,7 +90,11 @@ fn reflect() {
         .into(),
         // make sure we can pass through custom native messages
         CustomMsg::Raw(Binary(b"{\"foo\":123}".to_vec())).into(),
-        CustomMsg::Debug("Hi, Dad!".to_string()).into(),
+        CustomMsg::Debug {
+            level: DebugLevel::Info,
+            msg: "Hi, Dad!".to_string(),
+        }
+        .into(),
         StakingMsg::Delegate {
             validator: HumanAddr::from("validator"),
             amount: coin(100, "ustake"),
@@ -1,7 +1,9 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
 use std::fmt;
 use std::ops::Deref;
+use std::str::FromStr;
 
 use crate::binary::Binary;
 
@@ -12,6 +14,14 @@ impl HumanAddr {
     pub fn as_str(&self) -> &str {
         &self.0
     }
+
+    /// Returns a lowercased copy of the address.
+    ///
+    /// Bech32 addresses must not be mixed-case, but user input sometimes is. Normalizing
+    /// before `Api::canonical_address` ensures both spellings map to the same canonical address.
+    pub fn to_normalized(&self) -> HumanAddr {
+        HumanAddr(self.0.to_lowercase())
+    }
 }
 
 impl fmt::Display for HumanAddr {
@@ -26,6 +36,16 @@ impl From<&str> for HumanAddr {
     }
 }
 
+/// Parsing a HumanAddr cannot fail since no validation is performed.
+/// Use `Api::canonical_address` to check if an address is valid.
+impl FromStr for HumanAddr {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(HumanAddr::from(s))
+    }
+}
+
 impl From<&HumanAddr> for HumanAddr {
     fn from(addr: &HumanAddr) -> Self {
         HumanAddr(addr.0.to_string())
@@ -64,6 +84,13 @@ impl Deref for HumanAddr {
     }
 }
 
+/// Allows passing a `&HumanAddr` to generic functions taking `impl AsRef<str>`.
+impl AsRef<str> for HumanAddr {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
 /// Implement `HumanAddr == str`, which gives us `&HumanAddr == &str`.
 /// Do we really need &HumanAddr comparisons?
 impl PartialEq<str> for HumanAddr {
@@ -128,6 +155,13 @@ impl Deref for CanonicalAddr {
     }
 }
 
+/// Allows passing a `&CanonicalAddr` to generic functions taking `impl AsRef<[u8]>`.
+impl AsRef<[u8]> for CanonicalAddr {
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
 impl CanonicalAddr {
     pub fn as_slice(&self) -> &[u8] {
         &self.0.as_slice()
@@ -146,6 +180,7 @@ impl fmt::Display for CanonicalAddr {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::serde::{from_slice, to_vec};
     use std::collections::hash_map::DefaultHasher;
     use std::collections::HashSet;
     use std::hash::{Hash, Hasher};
@@ -178,6 +213,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn human_addr_to_normalized_works() {
+        let human_addr = HumanAddr::from("Cos934GH9034hg04g0H134");
+        assert_eq!(
+            human_addr.to_normalized(),
+            HumanAddr::from("cos934gh9034hg04g0h134")
+        );
+
+        let human_addr = HumanAddr::from("cos934gh9034hg04g0h134");
+        assert_eq!(human_addr.to_normalized(), human_addr);
+    }
+
+    #[test]
+    fn human_addr_implements_from_str() {
+        let human_addr = HumanAddr::from_str("cos934gh9034hg04g0h134").unwrap();
+        assert_eq!(human_addr, HumanAddr::from("cos934gh9034hg04g0h134"));
+
+        let human_addr: HumanAddr = "cos934gh9034hg04g0h134".parse().unwrap();
+        assert_eq!(human_addr.as_str(), "cos934gh9034hg04g0h134");
+
+        let empty: HumanAddr = "".parse().unwrap();
+        assert_eq!(empty, HumanAddr::default());
+    }
+
     #[test]
     fn human_addr_implements_display() {
         let human_addr = HumanAddr::from("cos934gh9034hg04g0h134");
@@ -199,6 +258,19 @@ mod tests {
         assert_eq!(human_addr_str, "cos934gh9034hg04g0h134");
     }
 
+    #[test]
+    fn human_addr_implements_as_ref_str() {
+        fn takes_as_ref<S: AsRef<str>>(value: S) -> usize {
+            value.as_ref().len()
+        }
+
+        let human_addr = HumanAddr::from("cos934gh9034hg04g0h134");
+        let human_addr_str: &str = human_addr.as_ref();
+        assert_eq!(human_addr_str, "cos934gh9034hg04g0h134");
+        assert_eq!(takes_as_ref(&human_addr), 22);
+        assert_eq!(takes_as_ref(human_addr), 22);
+    }
+
     #[test]
     fn human_addr_implements_partial_eq_with_str() {
         let addr = HumanAddr::from("cos934gh9034hg04g0h134");
@@ -349,6 +421,20 @@ mod tests {
         assert_eq!(address.to_string(), "1203AB00FF");
     }
 
+    #[test]
+    fn canonical_addr_implements_as_ref_bytes() {
+        fn takes_as_ref<B: AsRef<[u8]>>(value: B) -> usize {
+            value.as_ref().len()
+        }
+
+        let bytes: &[u8] = &[0u8, 187, 61, 11, 250, 0];
+        let address = CanonicalAddr::from(bytes);
+        let address_bytes: &[u8] = address.as_ref();
+        assert_eq!(address_bytes, bytes);
+        assert_eq!(takes_as_ref(&address), 6);
+        assert_eq!(takes_as_ref(address), 6);
+    }
+
     #[test]
     fn canonical_addr_implements_deref() {
         // Dereference to [u8]
@@ -402,4 +488,39 @@ mod tests {
         let set2 = HashSet::from_iter(vec![alice1.clone(), alice2.clone(), bob.clone()]);
         assert_eq!(set1, set2);
     }
+
+    #[test]
+    fn human_addr_serde_round_trip() {
+        let cases = vec![
+            (HumanAddr::from(""), r#""""#.to_string()),
+            (HumanAddr::from("cosmos1"), r#""cosmos1""#.to_string()),
+            (
+                HumanAddr::from("a".repeat(255)),
+                format!(r#""{}""#, "a".repeat(255)),
+            ),
+            (HumanAddr::from("Grüße-🦀"), r#""Grüße-🦀""#.to_string()),
+        ];
+        for (addr, json) in cases {
+            let serialized = to_vec(&addr).unwrap();
+            assert_eq!(String::from_utf8(serialized.clone()).unwrap(), json);
+            let deserialized: HumanAddr = from_slice(&serialized).unwrap();
+            assert_eq!(deserialized, addr);
+        }
+    }
+
+    #[test]
+    fn canonical_addr_serde_round_trip() {
+        // the base64 encoding must be stable as it is used in contract state
+        let addr = CanonicalAddr(Binary::from([0, 187, 61, 11, 250, 0]));
+        let serialized = to_vec(&addr).unwrap();
+        assert_eq!(serialized, br#""ALs9C/oA""#);
+        let deserialized: CanonicalAddr = from_slice(&serialized).unwrap();
+        assert_eq!(deserialized, addr);
+
+        let empty = CanonicalAddr::from(vec![]);
+        let serialized = to_vec(&empty).unwrap();
+        assert_eq!(serialized, br#""""#);
+        let deserialized: CanonicalAddr = from_slice(&serialized).unwrap();
+        assert_eq!(deserialized, empty);
+    }
 }
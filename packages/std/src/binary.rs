@@ -1,6 +1,7 @@
 use std::fmt;
 use std::mem;
 use std::ops::Deref;
+use std::ptr;
 
 use schemars::JsonSchema;
 use serde::{de, ser, Deserialize, Deserializer, Serialize};
@@ -74,6 +75,48 @@ impl Binary {
         <A as AsMut<[u8]>>::as_mut(&mut out).copy_from_slice(&self.0);
         Ok(out)
     }
+
+    /// Compares the content with `other` in constant time, i.e. the runtime does not depend
+    /// on the position of the first differing byte. Use this to compare secrets like preimages.
+    ///
+    /// Only the content is protected. Inputs of different length are rejected immediately,
+    /// which reveals whether the lengths match. Do not use this if the length is secret.
+    ///
+    /// The accumulated difference is read through a volatile read to keep the optimizer from
+    /// turning the comparison into an early return. This is a best effort, not a guarantee
+    /// on every compiler and target.
+    pub fn ct_eq(&self, other: &Binary) -> bool {
+        if self.len() != other.len() {
+            return false;
+        }
+        let diff = self
+            .0
+            .iter()
+            .zip(other.0.iter())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b));
+        // `core::hint::black_box` is not available in our minimum supported Rust version
+        let diff = unsafe { ptr::read_volatile(&diff) };
+        diff == 0
+    }
+}
+
+/// Stores the given bytes verbatim. This is different from `to_binary`,
+/// which JSON encodes its argument, e.g. `to_binary(&vec![1u8, 2])` results in the bytes
+/// of the JSON array `[1,2]`.
+///
+/// Use this when the data is already in its final byte representation, e.g. for the
+/// `data` field of a response that should not be JSON.
+///
+/// # Examples
+///
+/// ```
+/// # use cosmwasm_std::{raw_binary, to_binary};
+/// let bytes = vec![0xAA, 0xBB];
+/// assert_eq!(raw_binary(bytes.clone()).as_slice(), &[0xAA, 0xBB]);
+/// assert_eq!(to_binary(&bytes).unwrap().as_slice(), b"[170,187]");
+/// ```
+pub fn raw_binary<B: Into<Vec<u8>>>(bytes: B) -> Binary {
+    Binary(bytes.into())
 }
 
 impl fmt::Display for Binary {
@@ -82,6 +125,7 @@ impl fmt::Display for Binary {
     }
 }
 
+/// Copies the bytes verbatim (see [`raw_binary`])
 impl From<&[u8]> for Binary {
     fn from(binary: &[u8]) -> Self {
         Self(binary.to_vec())
@@ -251,7 +295,7 @@ implement_fixes_size_arrays! {
 mod tests {
     use super::*;
     use crate::errors::StdError;
-    use crate::serde::{from_slice, to_vec};
+    use crate::serde::{from_slice, to_binary, to_vec};
     use std::collections::hash_map::DefaultHasher;
     use std::collections::HashSet;
     use std::hash::{Hash, Hasher};
@@ -275,6 +319,40 @@ mod tests {
         assert_eq!(binary.deref(), decoded.deref());
     }
 
+    #[test]
+    fn raw_binary_keeps_bytes_verbatim() {
+        let bytes: Vec<u8> = vec![0x7b, 0x00, 0xff];
+        let raw = raw_binary(bytes.clone());
+        assert_eq!(raw.as_slice(), &[0x7b, 0x00, 0xff]);
+        assert_eq!(raw, Binary::from(bytes.as_slice()));
+        assert_eq!(raw_binary(&b"raw"[..]), Binary::from(b"raw"));
+
+        // JSON encoding creates an array of numbers instead
+        let json = to_binary(&bytes).unwrap();
+        assert_eq!(json.as_slice(), b"[123,0,255]");
+        assert_ne!(json, raw);
+
+        // and a Binary is JSON encoded as a base64 string
+        let json = to_binary(&raw).unwrap();
+        assert_eq!(json.as_slice(), br#""ewD/""#);
+    }
+
+    #[test]
+    fn ct_eq_works() {
+        // equal
+        assert!(Binary::from(b"").ct_eq(&Binary::from(b"")));
+        assert!(Binary::from(b"secret").ct_eq(&Binary::from(b"secret")));
+
+        // same length, different content
+        assert!(!Binary::from(b"secret").ct_eq(&Binary::from(b"secreT")));
+        assert!(!Binary::from(b"secret").ct_eq(&Binary::from(b"Secret")));
+        assert!(!Binary::from(&[0x00]).ct_eq(&Binary::from(&[0x80])));
+
+        // different length
+        assert!(!Binary::from(b"secret").ct_eq(&Binary::from(b"secrets")));
+        assert!(!Binary::from(b"secret").ct_eq(&Binary::from(b"")));
+    }
+
     #[test]
     fn to_array_works() {
         // simple
@@ -287,7 +365,7 @@ mod tests {
         let array: [u8; 0] = binary.to_array().unwrap();
         assert_eq!(array, [] as [u8; 0]);
 
-        // invalid size
+        // invalid size (too short)
         let binary = Binary::from(&[1, 2, 3]);
         let error = binary.to_array::<[u8; 8]>().unwrap_err();
         match error {
@@ -300,6 +378,19 @@ mod tests {
             err => panic!("Unexpected error: {:?}", err),
         }
 
+        // invalid size (too long)
+        let binary = Binary::from(&[1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        let error = binary.to_array::<[u8; 8]>().unwrap_err();
+        match error {
+            StdError::InvalidDataSize {
+                expected, actual, ..
+            } => {
+                assert_eq!(expected, 8);
+                assert_eq!(actual, 9);
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+
         // long array (32 bytes)
         let binary = Binary::from_base64("t119JOQox4WUQEmO/nyqOZfO+wjJm91YG2sfn4ZglvA=").unwrap();
         let array: [u8; 32] = binary.to_array().unwrap();
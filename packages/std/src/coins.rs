@@ -1,6 +1,8 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
+use crate::errors::{StdError, StdResult};
 use crate::math::Uint128;
 
 #[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, JsonSchema)]
@@ -16,6 +18,43 @@ impl Coin {
             denom: denom.into(),
         }
     }
+
+    /// Checks the denom against the rules of the Cosmos SDK bank module, i.e.
+    /// 3 to 128 characters, starting with a letter, followed by letters, digits or `/`.
+    ///
+    /// See https://github.com/cosmos/cosmos-sdk/blob/v0.40.0/types/coin.go#L597
+    pub fn validate_denom(&self) -> StdResult<()> {
+        let denom = self.denom.as_bytes();
+        if denom.len() < 3 || denom.len() > 128 {
+            return Err(StdError::generic_err(format!(
+                "Invalid denom '{}': length must be between 3 and 128",
+                self.denom
+            )));
+        }
+        if !denom[0].is_ascii_alphabetic() {
+            return Err(StdError::generic_err(format!(
+                "Invalid denom '{}': must start with a letter",
+                self.denom
+            )));
+        }
+        if !denom[1..]
+            .iter()
+            .all(|c| c.is_ascii_alphanumeric() || *c == b'/')
+        {
+            return Err(StdError::generic_err(format!(
+                "Invalid denom '{}': contains illegal characters",
+                self.denom
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Formats the coin as amount followed by denom, e.g. "123ucosm"
+impl fmt::Display for Coin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.amount, self.denom)
+    }
 }
 
 /// A shortcut constructor for a set of one denomination of coins
@@ -63,6 +102,23 @@ pub fn coin<S: Into<String>>(amount: u128, denom: S) -> Coin {
     Coin::new(amount, denom)
 }
 
+/// Like `coin` but returns an error if the denom is not valid (see `Coin::validate_denom`)
+pub fn coin_checked<S: Into<String>>(amount: u128, denom: S) -> StdResult<Coin> {
+    let coin = Coin::new(amount, denom);
+    coin.validate_denom()?;
+    Ok(coin)
+}
+
+/// Formats a list of coins separated by commas, e.g. "123ucosm,5ustake".
+/// An empty list results in an empty string.
+pub fn coins_to_string(coins: &[Coin]) -> String {
+    coins
+        .iter()
+        .map(|coin| coin.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
 /// has_coins returns true if the list of coins has at least the required amount
 pub fn has_coins(coins: &[Coin], required: &Coin) -> bool {
     coins
@@ -75,6 +131,7 @@ pub fn has_coins(coins: &[Coin], required: &Coin) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::from_slice;
 
     #[test]
     fn coin_works() {
@@ -136,6 +193,69 @@ mod tests {
         );
     }
 
+    #[test]
+    fn validate_denom_works() {
+        assert!(coin(1, "ucosm").validate_denom().is_ok());
+        assert!(coin(1, "FLY").validate_denom().is_ok());
+        assert!(coin(
+            1,
+            "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"
+        )
+        .validate_denom()
+        .is_ok());
+        assert!(coin(1, "a".repeat(128)).validate_denom().is_ok());
+
+        // empty
+        match coin(1, "").validate_denom().unwrap_err() {
+            StdError::GenericErr { msg, .. } => assert!(msg.contains("length")),
+            e => panic!("Unexpected error: {:?}", e),
+        }
+        // too short
+        match coin(1, "ab").validate_denom().unwrap_err() {
+            StdError::GenericErr { msg, .. } => assert!(msg.contains("length")),
+            e => panic!("Unexpected error: {:?}", e),
+        }
+        // too long
+        match coin(1, "a".repeat(129)).validate_denom().unwrap_err() {
+            StdError::GenericErr { msg, .. } => assert!(msg.contains("length")),
+            e => panic!("Unexpected error: {:?}", e),
+        }
+        // first character not a letter
+        match coin(1, "1token").validate_denom().unwrap_err() {
+            StdError::GenericErr { msg, .. } => assert!(msg.contains("start with a letter")),
+            e => panic!("Unexpected error: {:?}", e),
+        }
+        // illegal characters
+        for denom in &["u cosm", "ucosm!", "u-cosm", "ucösm"] {
+            match coin(1, *denom).validate_denom().unwrap_err() {
+                StdError::GenericErr { msg, .. } => assert!(msg.contains("illegal characters")),
+                e => panic!("Unexpected error: {:?}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn coin_checked_works() {
+        assert_eq!(coin_checked(123, "ucosm").unwrap(), coin(123, "ucosm"));
+        coin_checked(123, "u$cosm").unwrap_err();
+    }
+
+    #[test]
+    fn coin_implements_display() {
+        assert_eq!(coin(123, "ucosm").to_string(), "123ucosm");
+        assert_eq!(coin(0, "ustake").to_string(), "0ustake");
+    }
+
+    #[test]
+    fn coins_to_string_works() {
+        assert_eq!(coins_to_string(&[]), "");
+        assert_eq!(coins_to_string(&coins(123, "token")), "123token");
+        assert_eq!(
+            coins_to_string(&[coin(123, "token"), coin(5, "earth")]),
+            "123token,5earth"
+        );
+    }
+
     #[test]
     fn has_coins_matches() {
         let wallet = vec![coin(12345, "ETH"), coin(555, "BTC")];
@@ -143,4 +263,20 @@ mod tests {
         // less than same type
         assert!(has_coins(&wallet, &coin(777, "ETH")));
     }
+
+    #[test]
+    fn coin_amount_must_be_integer_string() {
+        let parsed: Coin = from_slice(br#"{"denom":"ucosm","amount":"123"}"#).unwrap();
+        assert_eq!(parsed, coin(123, "ucosm"));
+
+        for amount in &["", "-5", "1.5"] {
+            let json = format!(r#"{{"denom":"ucosm","amount":"{}"}}"#, amount);
+            match from_slice::<Coin>(json.as_bytes()).unwrap_err() {
+                StdError::ParseErr { msg, .. } => {
+                    assert!(msg.contains(&format!("invalid Uint128 '{}'", amount)))
+                }
+                e => panic!("Unexpected error: {:?}", e),
+            }
+        }
+    }
 }
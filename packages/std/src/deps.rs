@@ -11,12 +11,25 @@ pub struct OwnedDeps<S: Storage, A: Api, Q: Querier> {
     pub querier: Q,
 }
 
+/// The dependencies passed to entry points that may modify state, like `init` and `handle`.
+///
+/// Use [`DepsMut::as_ref`] to pass a read-only view to helpers that should not write.
 pub struct DepsMut<'a> {
     pub storage: &'a mut dyn Storage,
     pub api: &'a dyn Api,
     pub querier: QuerierWrapper<'a>,
 }
 
+/// The read-only dependencies passed to `query`. Helpers that must not modify state should
+/// take this type, since storage cannot be written through it:
+///
+/// ```compile_fail
+/// use cosmwasm_std::Deps;
+///
+/// fn helper(deps: Deps) {
+///     deps.storage.set(b"foo", b"bar");
+/// }
+/// ```
 #[derive(Copy, Clone)]
 pub struct Deps<'a> {
     pub storage: &'a dyn Storage,
@@ -40,9 +53,20 @@ impl<S: Storage, A: Api, Q: Querier> OwnedDeps<S, A, Q> {
             querier: QuerierWrapper::new(&self.querier),
         }
     }
+
+    /// Replaces the querier while keeping storage and api. The new querier can be of a
+    /// different type. This allows re-using the same state with a differently seeded querier in tests.
+    pub fn with_querier<Q2: Querier>(self, querier: Q2) -> OwnedDeps<S, A, Q2> {
+        OwnedDeps {
+            storage: self.storage,
+            api: self.api,
+            querier,
+        }
+    }
 }
 
 impl<'a> DepsMut<'a> {
+    /// Returns a read-only view of the dependencies, e.g. to call query helpers from `handle`.
     pub fn as_ref(&'_ self) -> Deps<'_> {
         Deps {
             storage: self.storage,
@@ -63,7 +87,8 @@ impl<'a> DepsMut<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::mock::mock_dependencies;
+    use crate::mock::{mock_dependencies, MockQuerier};
+    use crate::{coin, coins, BalanceResponse, BankQuery, Empty, HumanAddr, QueryRequest};
 
     // ensure we can call these many times, eg. as sub-calls
     fn handle(mut deps: DepsMut) {
@@ -85,4 +110,25 @@ mod tests {
         handle(deps.as_mut());
         query(deps.as_ref())
     }
+
+    #[test]
+    fn with_querier_works() {
+        let mut deps = mock_dependencies(&coins(123, "ucosm"));
+        deps.storage.set(b"foo", b"bar");
+
+        let querier: MockQuerier<Empty> =
+            MockQuerier::new(&[(&HumanAddr::from("alice"), &coins(7, "ustake"))]);
+        let deps = deps.with_querier(querier);
+
+        // storage is kept
+        assert_eq!(deps.storage.get(b"foo"), Some(b"bar".to_vec()));
+        // queries are answered by the new querier
+        let request: QueryRequest<Empty> = BankQuery::Balance {
+            address: HumanAddr::from("alice"),
+            denom: "ustake".to_string(),
+        }
+        .into();
+        let response: BalanceResponse = deps.as_ref().querier.query(&request).unwrap();
+        assert_eq!(response.amount, coin(7, "ustake"));
+    }
 }
@@ -14,6 +14,10 @@ use thiserror::Error;
 /// InvalidBase64 is preferred over Base64DecodingErr). In the long run this allows us to get rid of
 /// the duplication in "StdError::FooErr".
 ///
+/// All cases store the underlying problem as a rendered message. The cases that wrap a lower-level
+/// error (`InvalidUtf8`, `ParseErr` and `SerializeErr`) additionally keep that error as their
+/// `std::error::Error::source` when created via `From` or the `*_from` constructors.
+///
 /// Checklist for adding a new error:
 /// - Add enum case
 /// - Add creator function in std_error_helpers.rs
@@ -43,6 +47,8 @@ pub enum StdError {
     #[error("Cannot decode UTF8 bytes into string: {msg}")]
     InvalidUtf8 {
         msg: String,
+        #[source]
+        source: Option<Box<dyn std::error::Error + Send + Sync + 'static>>,
         #[cfg(feature = "backtraces")]
         backtrace: Backtrace,
     },
@@ -57,6 +63,8 @@ pub enum StdError {
         /// the target type that was attempted
         target_type: String,
         msg: String,
+        #[source]
+        source: Option<Box<dyn std::error::Error + Send + Sync + 'static>>,
         #[cfg(feature = "backtraces")]
         backtrace: Backtrace,
     },
@@ -65,6 +73,8 @@ pub enum StdError {
         /// the source type that was attempted
         source_type: String,
         msg: String,
+        #[source]
+        source: Option<Box<dyn std::error::Error + Send + Sync + 'static>>,
         #[cfg(feature = "backtraces")]
         backtrace: Backtrace,
     },
@@ -75,6 +85,21 @@ pub enum StdError {
         #[cfg(feature = "backtraces")]
         backtrace: Backtrace,
     },
+    /// Whenever a human address cannot be validated or canonicalized
+    #[error("Invalid address {addr}: {msg}")]
+    InvalidAddress {
+        addr: String,
+        msg: String,
+        #[cfg(feature = "backtraces")]
+        backtrace: Backtrace,
+    },
+    /// Whenever a value does not fit into the target integer type or an operation exceeds its range
+    #[error("Overflow: {msg}")]
+    Overflow {
+        msg: String,
+        #[cfg(feature = "backtraces")]
+        backtrace: Backtrace,
+    },
 }
 
 impl StdError {
@@ -107,6 +132,20 @@ impl StdError {
     pub fn invalid_utf8<S: ToString>(msg: S) -> Self {
         StdError::InvalidUtf8 {
             msg: msg.to_string(),
+            source: None,
+            #[cfg(feature = "backtraces")]
+            backtrace: Backtrace::capture(),
+        }
+    }
+
+    /// Like `invalid_utf8` but keeps `source` as the source of the resulting error
+    pub fn invalid_utf8_from<E>(source: E) -> Self
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        StdError::InvalidUtf8 {
+            msg: source.to_string(),
+            source: Some(Box::new(source)),
             #[cfg(feature = "backtraces")]
             backtrace: Backtrace::capture(),
         }
@@ -124,6 +163,22 @@ impl StdError {
         StdError::ParseErr {
             target_type: target.into(),
             msg: msg.to_string(),
+            source: None,
+            #[cfg(feature = "backtraces")]
+            backtrace: Backtrace::capture(),
+        }
+    }
+
+    /// Like `parse_err` but keeps `source` as the source of the resulting error
+    pub fn parse_err_from<T, E>(target: T, source: E) -> Self
+    where
+        T: Into<String>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        StdError::ParseErr {
+            target_type: target.into(),
+            msg: source.to_string(),
+            source: Some(Box::new(source)),
             #[cfg(feature = "backtraces")]
             backtrace: Backtrace::capture(),
         }
@@ -133,6 +188,22 @@ impl StdError {
         StdError::SerializeErr {
             source_type: source.into(),
             msg: msg.to_string(),
+            source: None,
+            #[cfg(feature = "backtraces")]
+            backtrace: Backtrace::capture(),
+        }
+    }
+
+    /// Like `serialize_err` but keeps `source` as the source of the resulting error
+    pub fn serialize_err_from<S, E>(source_type: S, source: E) -> Self
+    where
+        S: Into<String>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        StdError::SerializeErr {
+            source_type: source_type.into(),
+            msg: source.to_string(),
+            source: Some(Box::new(source)),
             #[cfg(feature = "backtraces")]
             backtrace: Backtrace::capture(),
         }
@@ -146,6 +217,23 @@ impl StdError {
             backtrace: Backtrace::capture(),
         }
     }
+
+    pub fn invalid_address<A: ToString, M: ToString>(addr: A, msg: M) -> Self {
+        StdError::InvalidAddress {
+            addr: addr.to_string(),
+            msg: msg.to_string(),
+            #[cfg(feature = "backtraces")]
+            backtrace: Backtrace::capture(),
+        }
+    }
+
+    pub fn overflow<S: ToString>(msg: S) -> Self {
+        StdError::Overflow {
+            msg: msg.to_string(),
+            #[cfg(feature = "backtraces")]
+            backtrace: Backtrace::capture(),
+        }
+    }
 }
 
 impl PartialEq<StdError> for StdError {
@@ -203,11 +291,13 @@ impl PartialEq<StdError> for StdError {
             }
             StdError::InvalidUtf8 {
                 msg,
+                source: _,
                 #[cfg(feature = "backtraces")]
                     backtrace: _,
             } => {
                 if let StdError::InvalidUtf8 {
                     msg: rhs_msg,
+                    source: _,
                     #[cfg(feature = "backtraces")]
                         backtrace: _,
                 } = rhs
@@ -236,12 +326,14 @@ impl PartialEq<StdError> for StdError {
             StdError::ParseErr {
                 target_type,
                 msg,
+                source: _,
                 #[cfg(feature = "backtraces")]
                     backtrace: _,
             } => {
                 if let StdError::ParseErr {
                     target_type: rhs_target_type,
                     msg: rhs_msg,
+                    source: _,
                     #[cfg(feature = "backtraces")]
                         backtrace: _,
                 } = rhs
@@ -254,12 +346,14 @@ impl PartialEq<StdError> for StdError {
             StdError::SerializeErr {
                 source_type,
                 msg,
+                source: _,
                 #[cfg(feature = "backtraces")]
                     backtrace: _,
             } => {
                 if let StdError::SerializeErr {
                     source_type: rhs_source_type,
                     msg: rhs_msg,
+                    source: _,
                     #[cfg(feature = "backtraces")]
                         backtrace: _,
                 } = rhs
@@ -287,19 +381,65 @@ impl PartialEq<StdError> for StdError {
                     false
                 }
             }
+            StdError::InvalidAddress {
+                addr,
+                msg,
+                #[cfg(feature = "backtraces")]
+                    backtrace: _,
+            } => {
+                if let StdError::InvalidAddress {
+                    addr: rhs_addr,
+                    msg: rhs_msg,
+                    #[cfg(feature = "backtraces")]
+                        backtrace: _,
+                } = rhs
+                {
+                    addr == rhs_addr && msg == rhs_msg
+                } else {
+                    false
+                }
+            }
+            StdError::Overflow {
+                msg,
+                #[cfg(feature = "backtraces")]
+                    backtrace: _,
+            } => {
+                if let StdError::Overflow {
+                    msg: rhs_msg,
+                    #[cfg(feature = "backtraces")]
+                        backtrace: _,
+                } = rhs
+                {
+                    msg == rhs_msg
+                } else {
+                    false
+                }
+            }
         }
     }
 }
 
 impl From<std::str::Utf8Error> for StdError {
     fn from(source: std::str::Utf8Error) -> Self {
-        Self::invalid_utf8(source)
+        Self::invalid_utf8_from(source)
     }
 }
 
 impl From<std::string::FromUtf8Error> for StdError {
     fn from(source: std::string::FromUtf8Error) -> Self {
-        Self::invalid_utf8(source)
+        Self::invalid_utf8_from(source)
+    }
+}
+
+impl From<std::num::ParseIntError> for StdError {
+    fn from(source: std::num::ParseIntError) -> Self {
+        Self::parse_err_from("integer", source)
+    }
+}
+
+impl From<std::num::TryFromIntError> for StdError {
+    fn from(source: std::num::TryFromIntError) -> Self {
+        Self::overflow(source)
     }
 }
 
@@ -469,6 +609,75 @@ mod tests {
         }
     }
 
+    #[test]
+    fn invalid_address_works() {
+        let error = StdError::invalid_address("x", "too short");
+        match error {
+            StdError::InvalidAddress { addr, msg, .. } => {
+                assert_eq!(addr, "x");
+                assert_eq!(msg, "too short");
+            }
+            _ => panic!("expect different error"),
+        }
+    }
+
+    #[test]
+    fn invalid_address_implements_display() {
+        let error = StdError::invalid_address("x", "too short");
+        assert_eq!(error.to_string(), "Invalid address x: too short");
+    }
+
+    #[test]
+    fn overflow_works() {
+        let error = StdError::overflow("value too large");
+        match error {
+            StdError::Overflow { msg, .. } => assert_eq!(msg, "value too large"),
+            _ => panic!("expect different error"),
+        }
+    }
+
+    #[test]
+    fn overflow_implements_display() {
+        let error = StdError::overflow("value too large");
+        assert_eq!(error.to_string(), "Overflow: value too large");
+    }
+
+    #[test]
+    fn from_std_num_parseinterror_works() {
+        let error: StdError = "12a".parse::<u64>().unwrap_err().into();
+        match error {
+            StdError::ParseErr {
+                target_type, msg, ..
+            } => {
+                assert_eq!(target_type, "integer");
+                assert_eq!(msg, "invalid digit found in string");
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+        let error: StdError = "12a".parse::<u64>().unwrap_err().into();
+        assert_eq!(
+            error.to_string(),
+            "Error parsing into type integer: invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn from_std_num_tryfrominterror_works() {
+        use std::convert::TryFrom;
+
+        let error: StdError = u8::try_from(300u32).unwrap_err().into();
+        match error {
+            StdError::Overflow { msg, .. } => {
+                assert_eq!(msg, "out of range integral type conversion attempted")
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+        assert_eq!(
+            StdError::from(u8::try_from(300u32).unwrap_err()).to_string(),
+            "Overflow: out of range integral type conversion attempted"
+        );
+    }
+
     #[test]
     fn implements_debug() {
         let error: StdError = StdError::underflow(3, 5);
@@ -486,6 +695,37 @@ mod tests {
         assert_eq!(embedded, "Display message: Cannot subtract 5 from 3");
     }
 
+    #[test]
+    fn source_chain_can_be_walked() {
+        use std::error::Error;
+
+        // errors converted from lower-level errors keep them as their source
+        let error: StdError = String::from_utf8(vec![0x80]).unwrap_err().into();
+        let source = error.source().expect("must have a source");
+        assert_eq!(
+            source.to_string(),
+            "invalid utf-8 sequence of 1 bytes from index 0"
+        );
+        assert!(source.is::<std::string::FromUtf8Error>());
+
+        let error: StdError = "12a".parse::<u64>().unwrap_err().into();
+        let source = error.source().expect("must have a source");
+        assert!(source.is::<std::num::ParseIntError>());
+
+        // errors created from messages have no source
+        let error = StdError::generic_err("not implemented");
+        assert!(error.source().is_none());
+        let error = StdError::invalid_utf8("bad bytes");
+        assert!(error.source().is_none());
+    }
+
+    #[test]
+    fn implements_partial_eq_ignoring_source() {
+        let with_source: StdError = "12a".parse::<u64>().unwrap_err().into();
+        let without_source = StdError::parse_err("integer", "invalid digit found in string");
+        assert_eq!(with_source, without_source);
+    }
+
     #[test]
     fn implements_partial_eq() {
         let u1 = StdError::underflow(3, 5);
@@ -22,8 +22,8 @@ mod traits;
 mod types;
 
 pub use crate::addresses::{CanonicalAddr, HumanAddr};
-pub use crate::binary::{Binary, ByteArray};
-pub use crate::coins::{coin, coins, has_coins, Coin};
+pub use crate::binary::{raw_binary, Binary, ByteArray};
+pub use crate::coins::{coin, coin_checked, coins, coins_to_string, has_coins, Coin};
 pub use crate::deps::{Deps, DepsMut, OwnedDeps};
 pub use crate::errors::{StdError, StdResult, SystemError};
 #[cfg(feature = "stargate")]
@@ -35,21 +35,23 @@ pub use crate::ibc::{
 #[cfg(feature = "iterator")]
 pub use crate::iterator::{Order, KV};
 pub use crate::math::{Decimal, Uint128};
+pub use crate::query::wasm as wasm_query;
 pub use crate::query::{
     AllBalanceResponse, AllDelegationsResponse, BalanceResponse, BankQuery, BondedDenomResponse,
-    CustomQuery, Delegation, FullDelegation, QueryRequest, StakingQuery, Validator,
-    ValidatorsResponse, WasmQuery,
+    CustomQuery, Delegation, DelegationResponse, FullDelegation, QueryRequest, StakingQuery,
+    Validator, ValidatorsResponse, WasmQuery,
 };
 pub use crate::results::{
-    attr, wasm_execute, wasm_instantiate, Attribute, BankMsg, ContractResult, CosmosMsg, Empty,
-    QueryResponse, Response, StakingMsg, SystemResult, WasmMsg,
+    attr, attr_truncated, wasm_execute, wasm_instantiate, Attribute, BankMsg, ContractResult,
+    CosmosMsg, Empty, Event, Output, QueryResponse, Reply, Response, StakingMsg,
+    SubMsgExecutionResponse, SystemResult, WasmMsg,
 };
 #[allow(deprecated)]
 pub use crate::results::{Context, HandleResponse, InitResponse, MigrateResponse};
-pub use crate::serde::{from_binary, from_slice, to_binary, to_vec};
+pub use crate::serde::{from_binary, from_slice, from_slice_borrowed, to_binary, to_vec};
 pub use crate::storage::MemoryStorage;
-pub use crate::traits::{Api, Querier, QuerierResult, QuerierWrapper, Storage};
-pub use crate::types::{BlockInfo, ContractInfo, Env, MessageInfo};
+pub use crate::traits::{Api, CachingQuerier, Querier, QuerierResult, QuerierWrapper, Storage};
+pub use crate::types::{BlockInfo, ContractInfo, Env, MessageInfo, TransactionInfo};
 
 // Exposed in wasm build only
 
@@ -81,9 +83,11 @@ mod mock;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod testing {
     pub use crate::mock::{
-        digit_sum, mock_dependencies, mock_dependencies_with_balances, mock_env, mock_info,
-        riffle_shuffle, BankQuerier, MockApi, MockQuerier, MockQuerierCustomHandlerResult,
-        MockStorage, StakingQuerier, MOCK_CONTRACT_ADDR,
+        digit_sum, mock_dependencies, mock_dependencies_with_balances, mock_env,
+        mock_env_at_height, mock_env_at_time, mock_env_with_chain_id, mock_env_with_contract,
+        mock_info, mock_info_normalized, riffle_shuffle, BankQuerier, GasCountingQuerier, MockApi,
+        MockQuerier, MockQuerierCustomHandlerResult, MockStorage, StakingQuerier,
+        MOCK_CONTRACT_ADDR,
     };
     #[cfg(feature = "stargate")]
     pub use crate::mock::{mock_ibc_channel, mock_ibc_packet_ack, mock_ibc_packet_recv};
@@ -1,3 +1,5 @@
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{InstanceType, Schema, SchemaObject, StringValidation};
 use schemars::JsonSchema;
 use serde::{de, ser, Deserialize, Deserializer, Serialize};
 use std::convert::TryFrom;
@@ -50,6 +52,30 @@ impl Decimal {
         Decimal(nominator * DECIMAL_FRACTIONAL / denominator)
     }
 
+    /// Returns the ratio (numerator / denominator) as a Decimal.
+    ///
+    /// Unlike `from_ratio` this does not panic but returns an error if the
+    /// denominator is zero or the result does not fit into a Decimal.
+    pub fn checked_from_ratio<A: Into<u128>, B: Into<u128>>(
+        numerator: A,
+        denominator: B,
+    ) -> StdResult<Decimal> {
+        let numerator: u128 = numerator.into();
+        let denominator: u128 = denominator.into();
+        if denominator == 0 {
+            return Err(StdError::generic_err("Denominator must not be zero"));
+        }
+        numerator
+            .checked_mul(DECIMAL_FRACTIONAL)
+            .map(|scaled| Decimal(scaled / denominator))
+            .ok_or_else(|| {
+                StdError::overflow(format!(
+                    "Ratio {}/{} does not fit into a Decimal",
+                    numerator, denominator
+                ))
+            })
+    }
+
     pub fn is_zero(&self) -> bool {
         self.0 == 0
     }
@@ -163,8 +189,8 @@ impl<'de> de::Visitor<'de> for DecimalVisitor {
 }
 
 //*** Uint128 ***/
-#[derive(Copy, Clone, Default, Debug, PartialEq, Eq, PartialOrd, Ord, JsonSchema)]
-pub struct Uint128(#[schemars(with = "String")] pub u128);
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Uint128(pub u128);
 
 impl Uint128 {
     /// Creates a Uint128(0)
@@ -205,6 +231,14 @@ impl TryFrom<&str> for Uint128 {
     }
 }
 
+impl FromStr for Uint128 {
+    type Err = StdError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Uint128::try_from(input)
+    }
+}
+
 impl Into<String> for Uint128 {
     fn into(self) -> String {
         self.0.to_string()
@@ -295,6 +329,18 @@ impl ops::Mul<Uint128> for Decimal {
 }
 
 impl Uint128 {
+    /// Returns self * decimal, rounded down. This is the checked version of `self * decimal`,
+    /// which returns an error instead of panicking if an intermediate result overflows.
+    pub fn checked_mul_decimal(&self, decimal: Decimal) -> StdResult<Uint128> {
+        if self.is_zero() || decimal.is_zero() {
+            return Ok(Uint128::zero());
+        }
+        self.0
+            .checked_mul(decimal.0)
+            .map(|product| Uint128(product / DECIMAL_FRACTIONAL))
+            .ok_or_else(|| StdError::overflow(format!("Cannot multiply {} by {}", self, decimal)))
+    }
+
     /// returns self * nom / denom
     pub fn multiply_ratio<A: Into<u128>, B: Into<u128>>(&self, nom: A, denom: B) -> Uint128 {
         let nominator: u128 = nom.into();
@@ -318,6 +364,25 @@ impl Serialize for Uint128 {
     }
 }
 
+/// Uint128 is represented as a string of decimal digits in JSON
+impl JsonSchema for Uint128 {
+    fn schema_name() -> String {
+        "Uint128".to_string()
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            string: Some(Box::new(StringValidation {
+                pattern: Some("^[0-9]+$".to_string()),
+                ..StringValidation::default()
+            })),
+            ..SchemaObject::default()
+        }
+        .into()
+    }
+}
+
 /// Deserializes as a base64 string
 impl<'de> Deserialize<'de> for Uint128 {
     fn deserialize<D>(deserializer: D) -> Result<Uint128, D::Error>
@@ -341,6 +406,13 @@ impl<'de> de::Visitor<'de> for Uint128Visitor {
     where
         E: de::Error,
     {
+        // `u128::from_str` also accepts a leading `+`, which we do not want here
+        if !v.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(E::custom(format!(
+                "invalid Uint128 '{}' - must only contain decimal digits",
+                v
+            )));
+        }
         match v.parse::<u128>() {
             Ok(u) => Ok(Uint128(u)),
             Err(e) => Err(E::custom(format!("invalid Uint128 '{}' - {}", v, e))),
@@ -420,6 +492,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn decimal_checked_from_ratio_works() {
+        assert_eq!(
+            Decimal::checked_from_ratio(1u128, 1u128).unwrap(),
+            Decimal::one()
+        );
+        assert_eq!(
+            Decimal::checked_from_ratio(3u128, 200u128).unwrap(),
+            Decimal::from_str("0.015").unwrap()
+        );
+        assert_eq!(
+            Decimal::checked_from_ratio(0u128, 3u128).unwrap(),
+            Decimal::zero()
+        );
+        // (2^128 - 1) / 10^18 is the largest numerator that does not overflow
+        let max_numerator = u128::MAX / DECIMAL_FRACTIONAL;
+        assert_eq!(
+            Decimal::checked_from_ratio(max_numerator, 1u128).unwrap(),
+            Decimal(max_numerator * DECIMAL_FRACTIONAL)
+        );
+
+        // zero denominator
+        match Decimal::checked_from_ratio(1u128, 0u128).unwrap_err() {
+            StdError::GenericErr { msg, .. } => assert_eq!(msg, "Denominator must not be zero"),
+            e => panic!("Unexpected error: {:?}", e),
+        }
+
+        // overflow
+        match Decimal::checked_from_ratio(max_numerator + 1, 1u128).unwrap_err() {
+            StdError::Overflow { msg, .. } => {
+                assert!(msg.ends_with("does not fit into a Decimal"))
+            }
+            e => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
     #[test]
     #[should_panic(expected = "Denominator must not be zero")]
     fn decimal_from_ratio_panics_for_zero_denominator() {
@@ -606,6 +714,32 @@ mod tests {
         assert_eq!(Decimal(100000000000000000).to_string(), "0.1");
     }
 
+    #[test]
+    fn decimal_string_round_trip() {
+        for value in &[
+            Decimal::zero(),
+            Decimal::one(),
+            Decimal(1),
+            Decimal::percent(1),
+            Decimal::permille(987),
+            Decimal::percent(42638),
+            Decimal::from_ratio(1u128, 3u128),
+            Decimal::MAX,
+        ] {
+            let string = value.to_string();
+            assert_eq!(string.parse::<Decimal>().unwrap(), *value);
+        }
+        assert_eq!(
+            Decimal::MAX.to_string(),
+            "340282366920938463463.374607431768211455"
+        );
+
+        // trailing zeros are accepted but not printed
+        assert_eq!("1.50".parse::<Decimal>().unwrap().to_string(), "1.5");
+        assert_eq!("1.000".parse::<Decimal>().unwrap().to_string(), "1");
+        assert_eq!("0.0".parse::<Decimal>().unwrap().to_string(), "0");
+    }
+
     #[test]
     fn decimal_serialize() {
         assert_eq!(to_vec(&Decimal::zero()).unwrap(), br#""0""#);
@@ -655,6 +789,30 @@ mod tests {
         assert!(a.is_err());
     }
 
+    #[test]
+    fn uint128_string_round_trip() {
+        for value in &[
+            Uint128::zero(),
+            Uint128(1),
+            Uint128(1015),
+            Uint128(u128::MAX),
+        ] {
+            let string = value.to_string();
+            assert_eq!(string.parse::<Uint128>().unwrap(), *value);
+        }
+        assert_eq!("0".parse::<Uint128>().unwrap(), Uint128::zero());
+        assert_eq!(
+            Uint128(u128::MAX).to_string(),
+            "340282366920938463463374607431768211455"
+        );
+
+        // values beyond the range and fractions are rejected
+        assert!("340282366920938463463374607431768211456"
+            .parse::<Uint128>()
+            .is_err());
+        assert!("1.0".parse::<Uint128>().is_err());
+    }
+
     #[test]
     fn uint128_is_zero_works() {
         assert_eq!(Uint128::zero().is_zero(), true);
@@ -673,6 +831,36 @@ mod tests {
         assert_eq!(parsed, orig);
     }
 
+    #[test]
+    fn uint128_json_rejects_non_integers() {
+        assert_eq!(from_slice::<Uint128>(br#""123""#).unwrap(), Uint128(123));
+
+        for (input, reason) in &[
+            (r#""""#, "cannot parse integer from empty string"),
+            (r#""-5""#, "must only contain decimal digits"),
+            (r#""1.5""#, "must only contain decimal digits"),
+            (r#""+5""#, "must only contain decimal digits"),
+            (r#""12a""#, "must only contain decimal digits"),
+        ] {
+            match from_slice::<Uint128>(input.as_bytes()).unwrap_err() {
+                StdError::ParseErr {
+                    target_type, msg, ..
+                } => {
+                    assert_eq!(target_type, "cosmwasm_std::math::Uint128");
+                    assert!(msg.contains(reason), "unexpected message: {}", msg);
+                }
+                e => panic!("Unexpected error: {:?}", e),
+            }
+        }
+
+        // overflow
+        let err =
+            from_slice::<Uint128>(br#""340282366920938463463374607431768211456""#).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("number too large to fit in target type"));
+    }
+
     #[test]
     fn uint128_compare() {
         let a = Uint128(12345);
@@ -743,6 +931,42 @@ mod tests {
         assert_eq!(left * right, Uint128(0));
     }
 
+    #[test]
+    fn uint128_checked_mul_decimal_works() {
+        // a*b
+        let fee = Decimal::percent(3);
+        assert_eq!(Uint128(1000).checked_mul_decimal(fee).unwrap(), Uint128(30));
+        let one_and_a_half = Decimal::one() + Decimal::percent(50);
+        assert_eq!(
+            Uint128(300).checked_mul_decimal(one_and_a_half).unwrap(),
+            Uint128(450)
+        );
+
+        // rounds down
+        assert_eq!(Uint128(33).checked_mul_decimal(fee).unwrap(), Uint128(0));
+
+        // a*0 and 0*a
+        assert_eq!(
+            Uint128(300).checked_mul_decimal(Decimal::zero()).unwrap(),
+            Uint128(0)
+        );
+        assert_eq!(
+            Uint128(0).checked_mul_decimal(Decimal::MAX).unwrap(),
+            Uint128(0)
+        );
+
+        // overflow
+        match Uint128(u128::MAX).checked_mul_decimal(fee).unwrap_err() {
+            StdError::Overflow { msg, .. } => {
+                assert_eq!(
+                    msg,
+                    "Cannot multiply 340282366920938463463374607431768211455 by 0.03"
+                )
+            }
+            e => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
     #[test]
     fn u128_multiply_ratio_works() {
         let base = Uint128(500);
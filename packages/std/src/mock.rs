@@ -1,7 +1,8 @@
 use serde::de::DeserializeOwned;
 #[cfg(feature = "stargate")]
 use serde::Serialize;
-use std::collections::HashMap;
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap};
 
 use crate::addresses::{CanonicalAddr, HumanAddr};
 use crate::binary::Binary;
@@ -129,6 +130,9 @@ impl Api for MockApi {
 /// You can submit as is to most contracts, or modify height/time if you want to
 /// test for expiration.
 ///
+/// The optional `env.contract.code_id` and `env.transaction` are unset, as they are
+/// on chains that do not provide them. Set them explicitly to test code relying on them.
+///
 /// This is intended for use in test code only.
 pub fn mock_env() -> Env {
     Env {
@@ -140,10 +144,50 @@ pub fn mock_env() -> Env {
         },
         contract: ContractInfo {
             address: HumanAddr::from(MOCK_CONTRACT_ADDR),
+            code_id: None,
         },
+        transaction: None,
     }
 }
 
+/// Returns a default enviroment like `mock_env`, but with the given block height.
+///
+/// This is intended for use in test code only.
+pub fn mock_env_at_height(height: u64) -> Env {
+    let mut env = mock_env();
+    env.block.height = height;
+    env
+}
+
+/// Returns a default enviroment like `mock_env`, but with the given block time
+/// in seconds since the UNIX epoch.
+///
+/// This is intended for use in test code only.
+pub fn mock_env_at_time(time: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = time;
+    env
+}
+
+/// Returns a default enviroment like `mock_env`, but with the given chain ID.
+///
+/// This is intended for use in test code only.
+pub fn mock_env_with_chain_id<U: Into<String>>(chain_id: U) -> Env {
+    let mut env = mock_env();
+    env.block.chain_id = chain_id.into();
+    env
+}
+
+/// Returns a default enviroment like `mock_env`, but with the given contract address.
+/// This allows simulating multiple instances of a contract.
+///
+/// This is intended for use in test code only.
+pub fn mock_env_with_contract<U: Into<HumanAddr>>(address: U) -> Env {
+    let mut env = mock_env();
+    env.contract.address = address.into();
+    env
+}
+
 /// Just set sender and funds for the message.
 /// This is intended for use in test code only.
 pub fn mock_info<U: Into<HumanAddr>>(sender: U, funds: &[Coin]) -> MessageInfo {
@@ -153,6 +197,25 @@ pub fn mock_info<U: Into<HumanAddr>>(sender: U, funds: &[Coin]) -> MessageInfo {
     }
 }
 
+/// Like `mock_info`, but normalizes the funds like the host does: amounts of the same
+/// denom are merged, zero amounts are removed and the result is sorted by denom.
+/// This is intended for use in test code only.
+pub fn mock_info_normalized<U: Into<HumanAddr>>(sender: U, funds: &[Coin]) -> MessageInfo {
+    let mut amounts: BTreeMap<&str, u128> = BTreeMap::new();
+    for coin in funds {
+        *amounts.entry(coin.denom.as_str()).or_default() += coin.amount.u128();
+    }
+    let funds = amounts
+        .into_iter()
+        .filter(|(_, amount)| *amount != 0)
+        .map(|(denom, amount)| Coin::new(amount, denom))
+        .collect();
+    MessageInfo {
+        sender: sender.into(),
+        funds,
+    }
+}
+
 #[cfg(feature = "stargate")]
 /// Creates an IbcChannel for testing. You set a few key parameters for handshaking,
 /// If you want to set more, use this as a default and mutate other fields
@@ -315,6 +378,43 @@ impl<C: CustomQuery + DeserializeOwned> MockQuerier<C> {
     }
 }
 
+/// Wraps another querier and charges a fixed synthetic gas cost for every query.
+/// This allows benchmarking the number of queries a contract makes.
+///
+/// The gas values are not related to the gas costs of a real chain.
+pub struct GasCountingQuerier<Q: Querier> {
+    inner: Q,
+    cost_per_query: u64,
+    gas_used: Cell<u64>,
+}
+
+impl<Q: Querier> GasCountingQuerier<Q> {
+    pub fn new(inner: Q, cost_per_query: u64) -> Self {
+        GasCountingQuerier {
+            inner,
+            cost_per_query,
+            gas_used: Cell::new(0),
+        }
+    }
+
+    /// The total synthetic gas charged since creation or the last reset
+    pub fn gas_used(&self) -> u64 {
+        self.gas_used.get()
+    }
+
+    pub fn reset_gas(&self) {
+        self.gas_used.set(0);
+    }
+}
+
+impl<Q: Querier> Querier for GasCountingQuerier<Q> {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        self.gas_used
+            .set(self.gas_used.get().saturating_add(self.cost_per_query));
+        self.inner.raw_query(bin_request)
+    }
+}
+
 #[derive(Clone, Default)]
 struct NoWasmQuerier {
     // FIXME: actually provide a way to call out
@@ -462,7 +562,81 @@ pub fn digit_sum(input: &[u8]) -> usize {
 mod tests {
     use super::*;
     use crate::query::Delegation;
-    use crate::{coin, coins, from_binary, Decimal, HumanAddr};
+    use crate::{
+        coin, coins, from_binary, from_slice, to_vec, Decimal, HumanAddr, QuerierWrapper,
+        TransactionInfo,
+    };
+
+    #[test]
+    fn mock_env_at_height_works() {
+        let early = mock_env_at_height(100);
+        let late = mock_env_at_height(200);
+        assert_eq!(early.block.height, 100);
+        assert_eq!(late.block.height, 200);
+        assert_ne!(early.block.height, late.block.height);
+
+        // everything else is the same as in mock_env
+        assert_eq!(early.block.time, mock_env().block.time);
+        assert_eq!(early.contract, mock_env().contract);
+    }
+
+    #[test]
+    fn mock_env_at_time_works() {
+        let env = mock_env_at_time(1_600_000_000);
+        assert_eq!(env.block.time, 1_600_000_000);
+        assert_eq!(env.block.height, mock_env().block.height);
+    }
+
+    #[test]
+    fn mock_env_with_chain_id_works() {
+        assert_eq!(mock_env().block.chain_id, "cosmos-testnet-14002");
+
+        let env = mock_env_with_chain_id("juno-1");
+        assert_eq!(env.block.chain_id, "juno-1");
+        assert_eq!(env.block.height, mock_env().block.height);
+        assert_eq!(env.contract, mock_env().contract);
+    }
+
+    #[test]
+    fn mock_env_with_contract_works() {
+        let env = mock_env_with_contract("cosmos2other");
+        assert_eq!(env.contract.address, HumanAddr::from("cosmos2other"));
+        assert_eq!(env.contract.code_id, None);
+        assert_eq!(env.block, mock_env().block);
+    }
+
+    #[test]
+    fn mock_env_contains_transaction_index() {
+        // unset by default
+        let mut env = mock_env();
+        assert_eq!(env.transaction, None);
+
+        env.transaction = Some(TransactionInfo { index: 3 });
+        let env: Env = from_slice(&to_vec(&env).unwrap()).unwrap();
+        assert_eq!(env.transaction, Some(TransactionInfo { index: 3 }));
+
+        // the transaction info is optional, e.g. when executing queries
+        let env: Env = from_slice(
+            br#"{"block":{"height":12345,"time":1571797419,"time_nanos":879305533,"chain_id":"cosmos-testnet-14002"},"contract":{"address":"cosmos2contract"}}"#,
+        )
+        .unwrap();
+        assert_eq!(env.transaction, None);
+    }
+
+    #[test]
+    fn mock_env_contains_code_id() {
+        // unset by default
+        let mut env = mock_env();
+        assert_eq!(env.contract.code_id, None);
+
+        env.contract.code_id = Some(7);
+        let contract: ContractInfo = from_slice(&to_vec(&env.contract).unwrap()).unwrap();
+        assert_eq!(contract.code_id, Some(7));
+
+        // the code ID is optional
+        let contract: ContractInfo = from_slice(br#"{"address":"cosmos2contract"}"#).unwrap();
+        assert_eq!(contract.code_id, None);
+    }
 
     #[test]
     fn mock_info_arguments() {
@@ -478,6 +652,23 @@ mod tests {
         assert_eq!(a, c);
     }
 
+    #[test]
+    fn mock_info_normalized_works() {
+        let funds = vec![
+            coin(5, "uatom"),
+            coin(0, "ufree"),
+            coin(100, "earth"),
+            coin(7, "uatom"),
+        ];
+        let info = mock_info_normalized("creator", &funds);
+        assert_eq!(info.sender, HumanAddr::from("creator"));
+        assert_eq!(info.funds, vec![coin(100, "earth"), coin(12, "uatom")]);
+
+        // mock_info keeps the funds as given
+        let info = mock_info("creator", &funds);
+        assert_eq!(info.funds, funds);
+    }
+
     #[test]
     fn canonicalize_and_humanize_restores_original() {
         let api = MockApi::default();
@@ -488,6 +679,27 @@ mod tests {
         assert_eq!(recovered, original);
     }
 
+    #[test]
+    fn addr_validate_to_canonical_works() {
+        let api = MockApi::default();
+
+        let human = HumanAddr::from("shorty");
+        let canonical = api.addr_validate_to_canonical(&human).unwrap();
+        assert_eq!(canonical, api.canonical_address(&human).unwrap());
+
+        let err = api
+            .addr_validate_to_canonical(&HumanAddr::from("x"))
+            .unwrap_err();
+        assert_eq!(
+            err,
+            StdError::invalid_address("x", "Invalid input: human address too short")
+        );
+        assert_eq!(
+            err.to_string(),
+            "Invalid address x: Invalid input: human address too short"
+        );
+    }
+
     #[test]
     #[should_panic(expected = "length not correct")]
     fn human_address_input_length() {
@@ -512,6 +724,26 @@ mod tests {
         let _ = api.canonical_address(&human).unwrap();
     }
 
+    #[test]
+    fn gas_counting_querier_works() {
+        let addr = HumanAddr::from("foobar");
+        let balance = vec![coin(123, "ELF"), coin(777, "FLY")];
+        let querier = GasCountingQuerier::new(MockQuerier::<Empty>::new(&[(&addr, &balance)]), 25);
+        assert_eq!(querier.gas_used(), 0);
+
+        let wrapper = QuerierWrapper::new(&querier);
+        for _ in 0..3 {
+            let all = wrapper.query_all_balances(&addr).unwrap();
+            assert_eq!(all, balance);
+        }
+        // queries for unknown accounts are charged as well
+        wrapper.query_balance("", "ELF").unwrap();
+        assert_eq!(querier.gas_used(), 4 * 25);
+
+        querier.reset_gas();
+        assert_eq!(querier.gas_used(), 0);
+    }
+
     #[test]
     fn bank_querier_all_balances() {
         let addr = HumanAddr::from("foobar");
@@ -586,6 +818,33 @@ mod tests {
         assert_eq!(res.amount, coin(0, "ELF"));
     }
 
+    #[test]
+    fn wasm_querier_missing_contract() {
+        let querier: MockQuerier = MockQuerier::new(&[]);
+
+        let smart = querier.handle_query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: HumanAddr::from("nowhere"),
+            msg: Binary::from(b"{}".to_vec()),
+        }));
+        match smart {
+            SystemResult::Err(SystemError::NoSuchContract { addr }) => {
+                assert_eq!(addr, HumanAddr::from("nowhere"))
+            }
+            res => panic!("Unexpected result: {:?}", res),
+        }
+
+        let raw = querier.handle_query(&QueryRequest::Wasm(WasmQuery::Raw {
+            contract_addr: HumanAddr::from("nowhere"),
+            key: Binary::from(b"config".to_vec()),
+        }));
+        match raw {
+            SystemResult::Err(SystemError::NoSuchContract { addr }) => {
+                assert_eq!(addr, HumanAddr::from("nowhere"))
+            }
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+
     #[test]
     fn staking_querier_validators() {
         let val1 = Validator {
@@ -9,6 +9,8 @@ use crate::ibc::IbcQuery;
 use crate::math::Decimal;
 use crate::results::Empty;
 
+pub mod wasm;
+
 #[non_exhaustive]
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -231,3 +233,97 @@ pub struct Validator {
     /// TODO: what units are these (in terms of time)?
     pub max_change_rate: Decimal,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serde::{from_slice, to_vec};
+
+    fn assert_round_trip(request: QueryRequest<Empty>, expected: &str) {
+        let serialized = String::from_utf8(to_vec(&request).unwrap()).unwrap();
+        assert_eq!(serialized, expected);
+        let deserialized: QueryRequest<Empty> = from_slice(expected.as_bytes()).unwrap();
+        assert_eq!(deserialized, request);
+    }
+
+    #[test]
+    fn query_request_bank_json_tags() {
+        assert_round_trip(
+            BankQuery::Balance {
+                address: HumanAddr::from("addr"),
+                denom: "ucosm".to_string(),
+            }
+            .into(),
+            r#"{"bank":{"balance":{"address":"addr","denom":"ucosm"}}}"#,
+        );
+        assert_round_trip(
+            BankQuery::AllBalances {
+                address: HumanAddr::from("addr"),
+            }
+            .into(),
+            r#"{"bank":{"all_balances":{"address":"addr"}}}"#,
+        );
+    }
+
+    #[test]
+    fn query_request_custom_json_tags() {
+        assert_round_trip(QueryRequest::Custom(Empty {}), r#"{"custom":{}}"#);
+    }
+
+    #[test]
+    fn query_request_staking_json_tags() {
+        assert_round_trip(
+            QueryRequest::Staking(StakingQuery::BondedDenom {}),
+            r#"{"staking":{"bonded_denom":{}}}"#,
+        );
+        assert_round_trip(
+            QueryRequest::Staking(StakingQuery::AllDelegations {
+                delegator: HumanAddr::from("addr"),
+            }),
+            r#"{"staking":{"all_delegations":{"delegator":"addr"}}}"#,
+        );
+        assert_round_trip(
+            QueryRequest::Staking(StakingQuery::Delegation {
+                delegator: HumanAddr::from("addr"),
+                validator: HumanAddr::from("val"),
+            }),
+            r#"{"staking":{"delegation":{"delegator":"addr","validator":"val"}}}"#,
+        );
+        assert_round_trip(
+            QueryRequest::Staking(StakingQuery::Validators {}),
+            r#"{"staking":{"validators":{}}}"#,
+        );
+    }
+
+    #[test]
+    fn query_request_wasm_json_tags() {
+        assert_round_trip(
+            WasmQuery::Smart {
+                contract_addr: HumanAddr::from("contract"),
+                msg: Binary::from(b"{}"),
+            }
+            .into(),
+            r#"{"wasm":{"smart":{"contract_addr":"contract","msg":"e30="}}}"#,
+        );
+        assert_round_trip(
+            WasmQuery::Raw {
+                contract_addr: HumanAddr::from("contract"),
+                key: Binary::from(b"config"),
+            }
+            .into(),
+            r#"{"wasm":{"raw":{"contract_addr":"contract","key":"Y29uZmln"}}}"#,
+        );
+    }
+
+    #[cfg(feature = "stargate")]
+    #[test]
+    fn query_request_stargate_json_tags() {
+        assert_round_trip(
+            QueryRequest::Stargate {
+                path: "/cosmos.bank.v1beta1.Query/Balance".to_string(),
+                data: Binary::from(b"data"),
+            },
+            r#"{"stargate":{"path":"/cosmos.bank.v1beta1.Query/Balance","data":"ZGF0YQ=="}}"#,
+        );
+    }
+}
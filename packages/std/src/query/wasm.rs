@@ -0,0 +1,85 @@
+//! Shortcut helpers to build `WasmQuery` requests, which can be quite verbose in contract code.
+//!
+//! This module is exported as `cosmwasm_std::wasm_query`.
+
+use serde::Serialize;
+
+use crate::addresses::HumanAddr;
+use crate::binary::Binary;
+use crate::errors::StdResult;
+use crate::serde::to_binary;
+
+use super::{CustomQuery, QueryRequest, WasmQuery};
+
+/// Creates a `WasmQuery::Smart` request for the given contract, JSON encoding `msg`
+pub fn smart<C, T, U>(contract_addr: T, msg: &U) -> StdResult<QueryRequest<C>>
+where
+    C: CustomQuery,
+    T: Into<HumanAddr>,
+    U: Serialize,
+{
+    Ok(WasmQuery::Smart {
+        contract_addr: contract_addr.into(),
+        msg: to_binary(msg)?,
+    }
+    .into())
+}
+
+/// Creates a `WasmQuery::Raw` request for the value stored at `key` in the given contract
+pub fn raw<C, T, U>(contract_addr: T, key: U) -> QueryRequest<C>
+where
+    C: CustomQuery,
+    T: Into<HumanAddr>,
+    U: Into<Binary>,
+{
+    WasmQuery::Raw {
+        contract_addr: contract_addr.into(),
+        key: key.into(),
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::results::Empty;
+    use crate::serde::to_vec;
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "snake_case")]
+    enum QueryMsg {
+        Verifier {},
+    }
+
+    #[test]
+    fn smart_works() {
+        let request: QueryRequest<Empty> = smart("contract", &QueryMsg::Verifier {}).unwrap();
+        assert_eq!(
+            request,
+            QueryRequest::Wasm(WasmQuery::Smart {
+                contract_addr: HumanAddr::from("contract"),
+                msg: Binary::from(br#"{"verifier":{}}"#),
+            })
+        );
+        assert_eq!(
+            to_vec(&request).unwrap(),
+            br#"{"wasm":{"smart":{"contract_addr":"contract","msg":"eyJ2ZXJpZmllciI6e319"}}}"#
+        );
+    }
+
+    #[test]
+    fn raw_works() {
+        let request: QueryRequest<Empty> = raw("contract", b"config");
+        assert_eq!(
+            request,
+            QueryRequest::Wasm(WasmQuery::Raw {
+                contract_addr: HumanAddr::from("contract"),
+                key: Binary::from(b"config"),
+            })
+        );
+        assert_eq!(
+            to_vec(&request).unwrap(),
+            br#"{"wasm":{"raw":{"contract_addr":"contract","key":"Y29uZmln"}}}"#
+        );
+    }
+}
@@ -16,6 +16,22 @@ pub fn attr<K: ToString, V: ToString>(key: K, value: V) -> Attribute {
     }
 }
 
+/// Creates a new Attribute with the value shortened to at most `max_len` characters.
+/// If the value was shortened, a trailing "…" is added to mark this.
+///
+/// This is useful to log potentially large values without blowing up the event size.
+pub fn attr_truncated<K: ToString, V: ToString>(key: K, value: V, max_len: usize) -> Attribute {
+    let value = value.to_string();
+    let value = match value.char_indices().nth(max_len) {
+        Some((end, _)) => format!("{}…", &value[..end]),
+        None => value,
+    };
+    Attribute {
+        key: key.to_string(),
+        value,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -36,4 +52,24 @@ mod tests {
         assert_eq!(attr("foo", Uint128(42)), expeceted);
         assert_eq!(attr("foo", 42), expeceted);
     }
+
+    #[test]
+    fn attr_truncated_works() {
+        // under and at the limit
+        assert_eq!(attr_truncated("foo", "bar", 5), attr("foo", "bar"));
+        assert_eq!(attr_truncated("foo", "barba", 5), attr("foo", "barba"));
+        assert_eq!(attr_truncated("foo", "", 0), attr("foo", ""));
+
+        // over the limit
+        assert_eq!(attr_truncated("foo", "barbaz", 5), attr("foo", "barba…"));
+        assert_eq!(
+            attr_truncated("foo", Uint128(123456), 3),
+            attr("foo", "123…")
+        );
+        assert_eq!(attr_truncated("foo", "bar", 0), attr("foo", "…"));
+
+        // limit is in characters, not bytes
+        assert_eq!(attr_truncated("foo", "äöüß", 3), attr("foo", "äöü…"));
+        assert_eq!(attr_truncated("foo", "äöü", 3), attr("foo", "äöü"));
+    }
 }
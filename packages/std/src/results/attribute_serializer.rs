@@ -0,0 +1,297 @@
+//! A minimal serde serializer that turns the top-level fields of a struct into attributes.
+//!
+//! Every field value is JSON encoded. Values that encode as JSON strings are unquoted,
+//! such that e.g. a `Uint128` or `HumanAddr` field results in its plain string representation.
+
+use serde::ser::{self, Impossible, Serialize};
+use std::any::type_name;
+use std::fmt;
+
+use crate::errors::{StdError, StdResult};
+use crate::serde::{from_slice, to_vec};
+
+use super::Attribute;
+
+/// Flattens the top-level fields of `value` into `{prefix}.{field}={value}` attributes,
+/// in field order.
+pub(crate) fn to_attributes<T: Serialize + ?Sized>(
+    prefix: &str,
+    value: &T,
+) -> StdResult<Vec<Attribute>> {
+    let mut attributes = vec![];
+    value
+        .serialize(AttributeSerializer {
+            prefix,
+            attributes: &mut attributes,
+        })
+        .map_err(|e| StdError::serialize_err(type_name::<T>(), e.0))?;
+    Ok(attributes)
+}
+
+#[derive(Debug)]
+struct Error(String);
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error(msg.to_string())
+    }
+}
+
+fn unsupported<T>(kind: &str) -> Result<T, Error> {
+    Err(Error(format!(
+        "Only structs can be converted to attributes, got {}",
+        kind
+    )))
+}
+
+/// Encodes a field value as JSON and unquotes it if it is a JSON string
+fn field_value<T: Serialize + ?Sized>(value: &T) -> Result<String, Error> {
+    let json = to_vec(value).map_err(|e| Error(e.to_string()))?;
+    if json.first() == Some(&b'"') {
+        from_slice(&json).map_err(|e| Error(e.to_string()))
+    } else {
+        String::from_utf8(json).map_err(|e| Error(e.to_string()))
+    }
+}
+
+struct AttributeSerializer<'a> {
+    prefix: &'a str,
+    attributes: &'a mut Vec<Attribute>,
+}
+
+impl<'a> ser::SerializeStruct for AttributeSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.attributes.push(Attribute {
+            key: format!("{}.{}", self.prefix, key),
+            value: field_value(value)?,
+        });
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'a> ser::Serializer for AttributeSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Impossible<(), Error>;
+    type SerializeTuple = Impossible<(), Error>;
+    type SerializeTupleStruct = Impossible<(), Error>;
+    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeMap = Impossible<(), Error>;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Impossible<(), Error>;
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self, Error> {
+        Ok(self)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_bool(self, _v: bool) -> Result<(), Error> {
+        unsupported("bool")
+    }
+
+    fn serialize_i8(self, _v: i8) -> Result<(), Error> {
+        unsupported("integer")
+    }
+
+    fn serialize_i16(self, _v: i16) -> Result<(), Error> {
+        unsupported("integer")
+    }
+
+    fn serialize_i32(self, _v: i32) -> Result<(), Error> {
+        unsupported("integer")
+    }
+
+    fn serialize_i64(self, _v: i64) -> Result<(), Error> {
+        unsupported("integer")
+    }
+
+    fn serialize_u8(self, _v: u8) -> Result<(), Error> {
+        unsupported("integer")
+    }
+
+    fn serialize_u16(self, _v: u16) -> Result<(), Error> {
+        unsupported("integer")
+    }
+
+    fn serialize_u32(self, _v: u32) -> Result<(), Error> {
+        unsupported("integer")
+    }
+
+    fn serialize_u64(self, _v: u64) -> Result<(), Error> {
+        unsupported("integer")
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<(), Error> {
+        unsupported("float")
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<(), Error> {
+        unsupported("float")
+    }
+
+    fn serialize_char(self, _v: char) -> Result<(), Error> {
+        unsupported("char")
+    }
+
+    fn serialize_str(self, _v: &str) -> Result<(), Error> {
+        unsupported("string")
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<(), Error> {
+        unsupported("bytes")
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        unsupported("option")
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, _value: &T) -> Result<(), Error> {
+        unsupported("option")
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        unsupported("unit")
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+        unsupported("unit struct")
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<(), Error> {
+        unsupported("enum")
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<(), Error> {
+        unsupported("enum")
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        unsupported("sequence")
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Error> {
+        unsupported("tuple")
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        unsupported("tuple struct")
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        unsupported("enum")
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        unsupported("map")
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        unsupported("enum")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::addresses::HumanAddr;
+    use crate::math::Uint128;
+    use crate::results::attr;
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    struct Payment {
+        recipient: HumanAddr,
+        amount: Uint128,
+        memo: Option<String>,
+        count: u32,
+        paid: bool,
+    }
+
+    #[test]
+    fn to_attributes_works() {
+        let payment = Payment {
+            recipient: HumanAddr::from("friend"),
+            amount: Uint128(1234),
+            memo: Some("say \"hi\"".to_string()),
+            count: 7,
+            paid: false,
+        };
+        let attributes = to_attributes("payment", &payment).unwrap();
+        assert_eq!(
+            attributes,
+            vec![
+                attr("payment.recipient", "friend"),
+                attr("payment.amount", "1234"),
+                attr("payment.memo", "say \"hi\""),
+                attr("payment.count", "7"),
+                attr("payment.paid", "false"),
+            ]
+        );
+    }
+
+    #[test]
+    fn to_attributes_rejects_non_structs() {
+        match to_attributes("x", &123u32).unwrap_err() {
+            StdError::SerializeErr { msg, .. } => {
+                assert_eq!(
+                    msg,
+                    "Only structs can be converted to attributes, got integer"
+                )
+            }
+            e => panic!("Unexpected error: {:?}", e),
+        }
+    }
+}
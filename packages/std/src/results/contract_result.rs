@@ -162,4 +162,42 @@ mod tests {
         let converted: Result<Response, String> = original.into();
         assert_eq!(converted, Err("went wrong".to_string()));
     }
+
+    #[test]
+    fn can_simulate_host_round_trip() {
+        use crate::{attr, Binary, SystemResult};
+
+        // the host wraps the contract output into a `SystemResult` and serializes it
+        let outputs: Vec<Result<Response, StdError>> = vec![
+            Ok(Response {
+                attributes: vec![attr("action", "reflect")],
+                data: Some(Binary::from(b"done")),
+                ..Response::default()
+            }),
+            Err(StdError::generic_err("broken")),
+        ];
+        let serialized: Vec<Vec<u8>> = outputs
+            .into_iter()
+            .map(|output| {
+                let wrapped: SystemResult<ContractResult<Response>> =
+                    SystemResult::Ok(output.into());
+                to_vec(&wrapped).unwrap()
+            })
+            .collect();
+        assert_eq!(
+            serialized[0],
+            br#"{"ok":{"ok":{"messages":[],"attributes":[{"key":"action","value":"reflect"}],"data":"ZG9uZQ=="}}}"#.to_vec()
+        );
+        assert_eq!(
+            serialized[1],
+            br#"{"ok":{"error":"Generic error: broken"}}"#.to_vec()
+        );
+
+        // the caller matches on the decoded value
+        let decoded: SystemResult<ContractResult<Response>> = from_slice(&serialized[1]).unwrap();
+        match decoded {
+            SystemResult::Ok(ContractResult::Err(err)) => assert_eq!(err, "Generic error: broken"),
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
 }
@@ -4,8 +4,8 @@ use std::fmt;
 
 use crate::addresses::HumanAddr;
 use crate::binary::Binary;
-use crate::coins::Coin;
-use crate::errors::StdResult;
+use crate::coins::{coins_to_string, Coin};
+use crate::errors::{StdError, StdResult};
 #[cfg(feature = "stargate")]
 use crate::ibc::IbcMsg;
 use crate::serde::to_binary;
@@ -52,6 +52,22 @@ pub enum BankMsg {
         to_address: HumanAddr,
         amount: Vec<Coin>,
     },
+    /// Sends native tokens from the contract to multiple addresses atomically.
+    ///
+    /// This requires host support, which no host provides yet. Until then, executing it fails.
+    /// A supporting host is expected to translate it to a [MsgMultiSend](https://github.com/cosmos/cosmos-sdk/blob/v0.40.0/proto/cosmos/bank/v1beta1/tx.proto#L33-L39)
+    /// with a single input, filled with the current contract's address and the sum of all outputs.
+    MultiSend {
+        /// The recipients and the tokens sent to each of them
+        outputs: Vec<Output>,
+    },
+}
+
+/// A recipient of a `BankMsg::MultiSend` along with the tokens sent to it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Output {
+    pub address: HumanAddr,
+    pub coins: Vec<Coin>,
 }
 
 /// The message types of the staking module.
@@ -128,6 +144,21 @@ pub enum WasmMsg {
         /// msg is the json-encoded MigrateMsg struct that will be passed to the new code
         msg: Binary,
     },
+    /// Sets a new admin (for migrate) on the given contract.
+    /// Fails if this contract is not currently admin of the target contract.
+    ///
+    /// This is translated to a [MsgUpdateAdmin](https://github.com/CosmWasm/wasmd/blob/v0.14.0/x/wasm/internal/types/tx.proto#L102-L109).
+    /// `sender` is automatically filled with the current contract's address.
+    UpdateAdmin {
+        contract_addr: HumanAddr,
+        admin: HumanAddr,
+    },
+    /// Clears the admin on the given contract, so no more migration possible.
+    /// Fails if this contract is not currently admin of the target contract.
+    ///
+    /// This is translated to a [MsgClearAdmin](https://github.com/CosmWasm/wasmd/blob/v0.14.0/x/wasm/internal/types/tx.proto#L114-L119).
+    /// `sender` is automatically filled with the current contract's address.
+    ClearAdmin { contract_addr: HumanAddr },
 }
 
 /// Shortcut helper as the construction of WasmMsg::Instantiate can be quite verbose in contract code
@@ -163,6 +194,197 @@ where
     })
 }
 
+impl<T: Clone + fmt::Debug + PartialEq + JsonSchema> CosmosMsg<T> {
+    /// Returns true if this message executes the contract `own_addr`, i.e. if a contract
+    /// with this address sends it, the contract calls itself. This can lead to reentrancy.
+    pub fn targets_self(&self, own_addr: &HumanAddr) -> bool {
+        matches!(
+            self,
+            CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) if contract_addr == own_addr
+        )
+    }
+}
+
+impl BankMsg {
+    /// Creates a `BankMsg::Send`, rejecting an empty `amount` or any zero coin,
+    /// which the host would reject when executing the message.
+    pub fn send_checked<U: Into<HumanAddr>>(to_address: U, amount: Vec<Coin>) -> StdResult<Self> {
+        if amount.is_empty() {
+            return Err(StdError::generic_err("Cannot send an empty amount"));
+        }
+        if let Some(coin) = amount.iter().find(|coin| coin.amount.is_zero()) {
+            return Err(StdError::generic_err(format!(
+                "Cannot send zero amount of {}",
+                coin.denom
+            )));
+        }
+        Ok(BankMsg::Send {
+            to_address: to_address.into(),
+            amount,
+        })
+    }
+
+    /// Converts the message into a `CosmosMsg<T>`. This is an explicit alternative to `.into()`
+    /// for call sites where the custom message type cannot be inferred.
+    pub fn into_cosmos_msg<T: Clone + fmt::Debug + PartialEq + JsonSchema>(self) -> CosmosMsg<T> {
+        CosmosMsg::Bank(self)
+    }
+}
+
+#[cfg(feature = "staking")]
+impl StakingMsg {
+    /// Converts the message into a `CosmosMsg<T>`. This is an explicit alternative to `.into()`
+    /// for call sites where the custom message type cannot be inferred.
+    pub fn into_cosmos_msg<T: Clone + fmt::Debug + PartialEq + JsonSchema>(self) -> CosmosMsg<T> {
+        CosmosMsg::Staking(self)
+    }
+}
+
+impl WasmMsg {
+    /// Converts the message into a `CosmosMsg<T>`. This is an explicit alternative to `.into()`
+    /// for call sites where the custom message type cannot be inferred.
+    pub fn into_cosmos_msg<T: Clone + fmt::Debug + PartialEq + JsonSchema>(self) -> CosmosMsg<T> {
+        CosmosMsg::Wasm(self)
+    }
+}
+
+/// A short, human readable summary of the message, e.g. `Bank::Send 1token -> friend`.
+/// This is meant for logging and not guaranteed to be stable.
+impl<T: Clone + fmt::Debug + PartialEq + JsonSchema> fmt::Display for CosmosMsg<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CosmosMsg::Bank(msg) => write!(f, "{}", msg),
+            CosmosMsg::Custom(msg) => write!(f, "Custom {:?}", msg),
+            CosmosMsg::Staking(msg) => write!(f, "{}", msg),
+            #[cfg(feature = "stargate")]
+            CosmosMsg::Stargate { type_url, .. } => write!(f, "Stargate {}", type_url),
+            #[cfg(feature = "stargate")]
+            CosmosMsg::Ibc(msg) => match msg {
+                IbcMsg::Transfer {
+                    channel_id,
+                    to_address,
+                    amount,
+                    ..
+                } => write!(
+                    f,
+                    "Ibc::Transfer {} -> {} via {}",
+                    amount, to_address, channel_id
+                ),
+                IbcMsg::SendPacket {
+                    channel_id, data, ..
+                } => write!(f, "Ibc::SendPacket {} bytes via {}", data.len(), channel_id),
+                IbcMsg::CloseChannel { channel_id } => {
+                    write!(f, "Ibc::CloseChannel {}", channel_id)
+                }
+            },
+            CosmosMsg::Wasm(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl fmt::Display for BankMsg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BankMsg::Send { to_address, amount } => write!(
+                f,
+                "Bank::Send {} -> {}",
+                coins_to_string(amount),
+                to_address
+            ),
+            BankMsg::MultiSend { outputs } => {
+                write!(f, "Bank::MultiSend")?;
+                for (index, output) in outputs.iter().enumerate() {
+                    let separator = if index == 0 { " " } else { ", " };
+                    write!(
+                        f,
+                        "{}{} -> {}",
+                        separator,
+                        coins_to_string(&output.coins),
+                        output.address
+                    )?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for StakingMsg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StakingMsg::Delegate { validator, amount } => {
+                write!(f, "Staking::Delegate {} -> {}", amount, validator)
+            }
+            StakingMsg::Undelegate { validator, amount } => {
+                write!(f, "Staking::Undelegate {} <- {}", amount, validator)
+            }
+            StakingMsg::Withdraw {
+                validator,
+                recipient,
+            } => match recipient {
+                Some(recipient) => {
+                    write!(f, "Staking::Withdraw {} -> {}", validator, recipient)
+                }
+                None => write!(f, "Staking::Withdraw {}", validator),
+            },
+            StakingMsg::Redelegate {
+                src_validator,
+                dst_validator,
+                amount,
+            } => write!(
+                f,
+                "Staking::Redelegate {} {} -> {}",
+                amount, src_validator, dst_validator
+            ),
+        }
+    }
+}
+
+impl fmt::Display for WasmMsg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WasmMsg::Execute {
+                contract_addr,
+                send,
+                ..
+            } => {
+                write!(f, "Wasm::Execute {}", contract_addr)?;
+                if !send.is_empty() {
+                    write!(f, " with {}", coins_to_string(send))?;
+                }
+                Ok(())
+            }
+            WasmMsg::Instantiate {
+                code_id,
+                send,
+                label,
+                ..
+            } => {
+                write!(f, "Wasm::Instantiate code {}", code_id)?;
+                if let Some(label) = label {
+                    write!(f, " ({})", label)?;
+                }
+                if !send.is_empty() {
+                    write!(f, " with {}", coins_to_string(send))?;
+                }
+                Ok(())
+            }
+            WasmMsg::Migrate {
+                contract_addr,
+                new_code_id,
+                ..
+            } => write!(f, "Wasm::Migrate {} -> code {}", contract_addr, new_code_id),
+            WasmMsg::UpdateAdmin {
+                contract_addr,
+                admin,
+            } => write!(f, "Wasm::UpdateAdmin {} -> {}", contract_addr, admin),
+            WasmMsg::ClearAdmin { contract_addr } => {
+                write!(f, "Wasm::ClearAdmin {}", contract_addr)
+            }
+        }
+    }
+}
+
 impl<T: Clone + fmt::Debug + PartialEq + JsonSchema> From<BankMsg> for CosmosMsg<T> {
     fn from(msg: BankMsg) -> Self {
         CosmosMsg::Bank(msg)
@@ -192,7 +414,7 @@ impl<T: Clone + fmt::Debug + PartialEq + JsonSchema> From<IbcMsg> for CosmosMsg<
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::coins;
+    use crate::{coin, coins, from_slice, to_vec};
 
     #[test]
     fn from_bank_msg_works() {
@@ -205,4 +427,257 @@ mod tests {
             _ => panic!("must encode in Bank variant"),
         }
     }
+
+    #[test]
+    fn targets_self_works() {
+        let own_addr = HumanAddr::from("me");
+
+        let msg: CosmosMsg = WasmMsg::Execute {
+            contract_addr: HumanAddr::from("me"),
+            msg: Binary::from(b"{}"),
+            send: vec![],
+        }
+        .into();
+        assert!(msg.targets_self(&own_addr));
+
+        let msg: CosmosMsg = WasmMsg::Execute {
+            contract_addr: HumanAddr::from("other"),
+            msg: Binary::from(b"{}"),
+            send: vec![],
+        }
+        .into();
+        assert!(!msg.targets_self(&own_addr));
+
+        // only executions count
+        let msg: CosmosMsg = WasmMsg::ClearAdmin {
+            contract_addr: HumanAddr::from("me"),
+        }
+        .into();
+        assert!(!msg.targets_self(&own_addr));
+        let msg: CosmosMsg = BankMsg::Send {
+            to_address: HumanAddr::from("me"),
+            amount: coins(1, "earth"),
+        }
+        .into();
+        assert!(!msg.targets_self(&own_addr));
+    }
+
+    #[test]
+    fn bank_msg_send_checked_works() {
+        let msg = BankMsg::send_checked("you", vec![coin(1015, "earth"), coin(1, "moon")]).unwrap();
+        assert_eq!(
+            msg,
+            BankMsg::Send {
+                to_address: HumanAddr::from("you"),
+                amount: vec![coin(1015, "earth"), coin(1, "moon")],
+            }
+        );
+
+        match BankMsg::send_checked("you", vec![]).unwrap_err() {
+            StdError::GenericErr { msg, .. } => assert_eq!(msg, "Cannot send an empty amount"),
+            e => panic!("Unexpected error: {:?}", e),
+        }
+        match BankMsg::send_checked("you", coins(0, "earth")).unwrap_err() {
+            StdError::GenericErr { msg, .. } => {
+                assert_eq!(msg, "Cannot send zero amount of earth")
+            }
+            e => panic!("Unexpected error: {:?}", e),
+        }
+        match BankMsg::send_checked("you", vec![coin(1, "earth"), coin(0, "moon")]).unwrap_err() {
+            StdError::GenericErr { msg, .. } => assert_eq!(msg, "Cannot send zero amount of moon"),
+            e => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn bank_msg_into_cosmos_msg_works() {
+        let bank = BankMsg::Send {
+            to_address: HumanAddr::from("you"),
+            amount: coins(1015, "earth"),
+        };
+        let msg = bank.clone().into_cosmos_msg::<Empty>();
+        assert_eq!(msg, CosmosMsg::Bank(bank.clone()));
+
+        // works with custom message types
+        let msg = bank.clone().into_cosmos_msg::<String>();
+        assert_eq!(msg, CosmosMsg::Bank(bank));
+    }
+
+    #[cfg(feature = "staking")]
+    #[test]
+    fn staking_msg_into_cosmos_msg_works() {
+        let staking = StakingMsg::Delegate {
+            validator: HumanAddr::from("validator"),
+            amount: Coin::new(100, "ustake"),
+        };
+        let msg = staking.clone().into_cosmos_msg::<Empty>();
+        assert_eq!(msg, CosmosMsg::Staking(staking.clone()));
+
+        let msg = staking.clone().into_cosmos_msg::<String>();
+        assert_eq!(msg, CosmosMsg::Staking(staking));
+    }
+
+    #[cfg(feature = "staking")]
+    #[test]
+    fn undelegate_round_trip() {
+        let msg: CosmosMsg = StakingMsg::Undelegate {
+            validator: HumanAddr::from("validator"),
+            amount: Coin::new(100, "ustake"),
+        }
+        .into();
+        let serialized = to_vec(&msg).unwrap();
+        assert_eq!(
+            serialized,
+            br#"{"staking":{"undelegate":{"validator":"validator","amount":{"denom":"ustake","amount":"100"}}}}"#
+        );
+        let deserialized: CosmosMsg = from_slice(&serialized).unwrap();
+        assert_eq!(deserialized, msg);
+    }
+
+    #[test]
+    fn wasm_msg_into_cosmos_msg_works() {
+        let wasm = WasmMsg::ClearAdmin {
+            contract_addr: HumanAddr::from("contract"),
+        };
+        let msg = wasm.clone().into_cosmos_msg::<Empty>();
+        assert_eq!(msg, CosmosMsg::Wasm(wasm.clone()));
+
+        let msg = wasm.clone().into_cosmos_msg::<String>();
+        assert_eq!(msg, CosmosMsg::Wasm(wasm));
+    }
+
+    #[test]
+    fn bank_msg_display_works() {
+        let msg: CosmosMsg = BankMsg::Send {
+            to_address: HumanAddr::from("friend"),
+            amount: coins(1, "token"),
+        }
+        .into();
+        assert_eq!(msg.to_string(), "Bank::Send 1token -> friend");
+
+        let msg: CosmosMsg = BankMsg::MultiSend {
+            outputs: vec![
+                Output {
+                    address: HumanAddr::from("alice"),
+                    coins: coins(1, "earth"),
+                },
+                Output {
+                    address: HumanAddr::from("bob"),
+                    coins: vec![coin(2, "earth"), coin(3, "moon")],
+                },
+            ],
+        }
+        .into();
+        assert_eq!(
+            msg.to_string(),
+            "Bank::MultiSend 1earth -> alice, 2earth,3moon -> bob"
+        );
+    }
+
+    #[cfg(feature = "staking")]
+    #[test]
+    fn staking_msg_display_works() {
+        let msg: CosmosMsg = StakingMsg::Delegate {
+            validator: HumanAddr::from("validator"),
+            amount: Coin::new(100, "ustake"),
+        }
+        .into();
+        assert_eq!(msg.to_string(), "Staking::Delegate 100ustake -> validator");
+
+        let msg: CosmosMsg = StakingMsg::Withdraw {
+            validator: HumanAddr::from("validator"),
+            recipient: None,
+        }
+        .into();
+        assert_eq!(msg.to_string(), "Staking::Withdraw validator");
+    }
+
+    #[test]
+    fn wasm_msg_display_works() {
+        let msg: CosmosMsg = WasmMsg::Execute {
+            contract_addr: HumanAddr::from("contract"),
+            msg: Binary::from(br#"{"release":{}}"#),
+            send: coins(5, "ucosm"),
+        }
+        .into();
+        assert_eq!(msg.to_string(), "Wasm::Execute contract with 5ucosm");
+
+        let msg: CosmosMsg = WasmMsg::Execute {
+            contract_addr: HumanAddr::from("contract"),
+            msg: Binary::from(br#"{"release":{}}"#),
+            send: vec![],
+        }
+        .into();
+        assert_eq!(msg.to_string(), "Wasm::Execute contract");
+
+        let msg: CosmosMsg = WasmMsg::Instantiate {
+            code_id: 12,
+            msg: Binary::from(b"{}"),
+            send: vec![],
+            label: Some("my token".to_string()),
+        }
+        .into();
+        assert_eq!(msg.to_string(), "Wasm::Instantiate code 12 (my token)");
+    }
+
+    #[test]
+    fn custom_msg_display_works() {
+        let msg: CosmosMsg<String> = CosmosMsg::Custom("foo".to_string());
+        assert_eq!(msg.to_string(), "Custom \"foo\"");
+    }
+
+    #[test]
+    fn multi_send_round_trip() {
+        let msg: CosmosMsg = BankMsg::MultiSend {
+            outputs: vec![
+                Output {
+                    address: HumanAddr::from("alice"),
+                    coins: coins(1, "earth"),
+                },
+                Output {
+                    address: HumanAddr::from("bob"),
+                    coins: coins(2, "earth"),
+                },
+            ],
+        }
+        .into();
+        let serialized = to_vec(&msg).unwrap();
+        assert_eq!(
+            serialized,
+            br#"{"bank":{"multi_send":{"outputs":[{"address":"alice","coins":[{"denom":"earth","amount":"1"}]},{"address":"bob","coins":[{"denom":"earth","amount":"2"}]}]}}}"#
+        );
+        let deserialized: CosmosMsg = from_slice(&serialized).unwrap();
+        assert_eq!(deserialized, msg);
+    }
+
+    #[test]
+    fn update_admin_round_trip() {
+        let msg: CosmosMsg = WasmMsg::UpdateAdmin {
+            contract_addr: HumanAddr::from("target"),
+            admin: HumanAddr::from("new-admin"),
+        }
+        .into();
+        let serialized = to_vec(&msg).unwrap();
+        assert_eq!(
+            serialized,
+            br#"{"wasm":{"update_admin":{"contract_addr":"target","admin":"new-admin"}}}"#
+        );
+        let deserialized: CosmosMsg = from_slice(&serialized).unwrap();
+        assert_eq!(deserialized, msg);
+    }
+
+    #[test]
+    fn clear_admin_round_trip() {
+        let msg: CosmosMsg = WasmMsg::ClearAdmin {
+            contract_addr: HumanAddr::from("target"),
+        }
+        .into();
+        let serialized = to_vec(&msg).unwrap();
+        assert_eq!(
+            serialized,
+            br#"{"wasm":{"clear_admin":{"contract_addr":"target"}}}"#
+        );
+        let deserialized: CosmosMsg = from_slice(&serialized).unwrap();
+        assert_eq!(deserialized, msg);
+    }
 }
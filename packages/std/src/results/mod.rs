@@ -1,22 +1,27 @@
 //! This module contains the messages that are sent from the contract to the VM as an execution result
 
 mod attribute;
+mod attribute_serializer;
 mod context;
 mod contract_result;
 mod cosmos_msg;
 mod empty;
 mod query;
 mod response;
+mod subcall;
 mod system_result;
 
-pub use attribute::{attr, Attribute};
+pub use attribute::{attr, attr_truncated, Attribute};
 #[allow(deprecated)]
 pub use context::Context;
 pub use contract_result::ContractResult;
-pub use cosmos_msg::{wasm_execute, wasm_instantiate, BankMsg, CosmosMsg, StakingMsg, WasmMsg};
+pub use cosmos_msg::{
+    wasm_execute, wasm_instantiate, BankMsg, CosmosMsg, Output, StakingMsg, WasmMsg,
+};
 pub use empty::Empty;
 pub use query::QueryResponse;
 pub use response::Response;
+pub use subcall::{Event, Reply, SubMsgExecutionResponse};
 pub use system_result::SystemResult;
 
 #[deprecated(since = "0.14.0", note = "Renamed to Response.")]
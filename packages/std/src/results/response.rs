@@ -1,11 +1,18 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt;
 
+use crate::errors::{StdError, StdResult};
+use crate::serde::to_vec;
 use crate::Binary;
 
+use super::attribute_serializer::to_attributes;
 use super::{Attribute, CosmosMsg, Empty};
 
+/// The attribute key set by `Response::set_json_data` and `Response::set_raw_data`
+const DATA_ENCODING_KEY: &str = "data_encoding";
+
 /// A response of a contract entry point, such as `init`, `handle` or `migrate`.
 ///
 /// This type can be constructed directly at the end of the call. Alternatively a
@@ -72,6 +79,8 @@ pub struct Response<T = Empty>
 where
     T: Clone + fmt::Debug + PartialEq + JsonSchema,
 {
+    // Fields added after `messages`, `attributes` and `data` must be marked with
+    // `#[serde(default)]` such that responses serialized by older versions can still be decoded.
     pub messages: Vec<CosmosMsg<T>>,
     /// The attributes that will be emitted as part of a "wasm" event
     pub attributes: Vec<Attribute>,
@@ -91,6 +100,16 @@ where
     }
 }
 
+/// Appends messages to the end of the message list, like `add_message` does for a single message.
+impl<T> Extend<CosmosMsg<T>> for Response<T>
+where
+    T: Clone + fmt::Debug + PartialEq + JsonSchema,
+{
+    fn extend<I: IntoIterator<Item = CosmosMsg<T>>>(&mut self, iter: I) {
+        self.messages.extend(iter);
+    }
+}
+
 impl<T> Response<T>
 where
     T: Clone + fmt::Debug + PartialEq + JsonSchema,
@@ -99,6 +118,27 @@ where
         Self::default()
     }
 
+    /// Creates a response with the given messages and no attributes or data
+    pub fn with_messages(messages: Vec<CosmosMsg<T>>) -> Self {
+        Response {
+            messages,
+            ..Self::default()
+        }
+    }
+
+    /// Creates a response for a `reply` entry point, echoing the data of the submessage
+    /// execution and adding the attribute `action=reply`
+    pub fn from_reply_data(data: Option<Binary>) -> Self {
+        Response {
+            attributes: vec![Attribute {
+                key: "action".to_string(),
+                value: "reply".to_string(),
+            }],
+            data,
+            ..Self::default()
+        }
+    }
+
     pub fn add_attribute<K: Into<String>, V: Into<String>>(&mut self, key: K, value: V) {
         self.attributes.push(Attribute {
             key: key.into(),
@@ -106,21 +146,424 @@ where
         });
     }
 
+    /// Appends the top-level fields of `value` as `{prefix}.{field}={value}` attributes.
+    ///
+    /// Field values are JSON encoded, except for strings, which are added as they are.
+    /// `value` must serialize as a struct.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cosmwasm_std::{attr, HumanAddr, Response, Uint128};
+    /// # use serde::Serialize;
+    /// #[derive(Serialize)]
+    /// struct Payment {
+    ///     recipient: HumanAddr,
+    ///     amount: Uint128,
+    /// }
+    ///
+    /// let payment = Payment {
+    ///     recipient: HumanAddr::from("friend"),
+    ///     amount: Uint128(12),
+    /// };
+    /// let mut response: Response = Response::new();
+    /// response.add_attributes_from("payment", &payment).unwrap();
+    /// assert_eq!(
+    ///     response.attributes,
+    ///     vec![attr("payment.recipient", "friend"), attr("payment.amount", "12")]
+    /// );
+    /// ```
+    pub fn add_attributes_from<U: Serialize>(&mut self, prefix: &str, value: &U) -> StdResult<()> {
+        let attributes = to_attributes(prefix, value)?;
+        self.attributes.extend(attributes);
+        Ok(())
+    }
+
+    /// Appends a message to the end of the message list.
+    ///
+    /// Messages are executed in the order in which they appear in `messages`,
+    /// i.e. in insertion order unless the list is reordered explicitly.
     pub fn add_message<U: Into<CosmosMsg<T>>>(&mut self, msg: U) {
         self.messages.push(msg.into());
     }
 
+    /// Like `add_message` but only appends the message if `cond` is true.
+    pub fn add_message_if<U: Into<CosmosMsg<T>>>(&mut self, cond: bool, msg: U) {
+        if cond {
+            self.add_message(msg);
+        }
+    }
+
     pub fn set_data<U: Into<Binary>>(&mut self, data: U) {
         self.data = Some(data.into());
     }
+
+    /// Removes the data from the response and returns it. This allows forwarding
+    /// the data without cloning it.
+    pub fn take_data(&mut self) -> Option<Binary> {
+        self.data.take()
+    }
+
+    /// Converts all attribute keys to lowercase. Values are not changed.
+    ///
+    /// This is opt-in and helps producing consistent keys for case-sensitive indexers.
+    pub fn normalize_attribute_keys(&mut self) {
+        for attribute in self.attributes.iter_mut() {
+            attribute.key = attribute.key.to_lowercase();
+        }
+    }
+
+    /// Removes all attributes with an empty value, keeping the order of the others.
+    ///
+    /// The host may drop such attributes when emitting events. Calling this before
+    /// returning the response makes the emitted attributes match the response exactly.
+    pub fn strip_empty_attributes(&mut self) {
+        self.attributes.retain(|attr| !attr.value.is_empty());
+    }
+
+    /// Returns the value of the first attribute with the given key, if any.
+    pub fn attribute(&self, key: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|attr| attr.key == key)
+            .map(|attr| attr.value.as_str())
+    }
+
+    /// Returns the values of all attributes with the given key in insertion order.
+    pub fn attributes_for(&self, key: &str) -> Vec<&str> {
+        self.attributes
+            .iter()
+            .filter(|attr| attr.key == key)
+            .map(|attr| attr.value.as_str())
+            .collect()
+    }
+
+    /// Groups the attribute values by key. Values of repeated keys are kept in insertion order.
+    ///
+    /// This is useful for off-chain tools and tests that look up attributes by key.
+    pub fn attributes_as_map(&self) -> BTreeMap<String, Vec<String>> {
+        let mut map: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for attribute in self.attributes.iter() {
+            map.entry(attribute.key.clone())
+                .or_default()
+                .push(attribute.value.clone());
+        }
+        map
+    }
+
+    /// Returns an error listing all keys that are used by more than one attribute.
+    ///
+    /// This is opt-in and helps with indexers that require unique keys per event.
+    pub fn require_unique_keys(&self) -> StdResult<()> {
+        let mut duplicates: Vec<&str> = vec![];
+        for (index, attribute) in self.attributes.iter().enumerate() {
+            let key = attribute.key.as_str();
+            if self.attributes[..index].iter().any(|prev| prev.key == key)
+                && !duplicates.contains(&key)
+            {
+                duplicates.push(key);
+            }
+        }
+        if duplicates.is_empty() {
+            Ok(())
+        } else {
+            Err(StdError::generic_err(format!(
+                "Duplicate attribute keys: {}",
+                duplicates.join(", ")
+            )))
+        }
+    }
+
+    /// Returns the key and size in bytes (key length plus value length) of every attribute,
+    /// in insertion order.
+    ///
+    /// This helps finding the culprit when an event exceeds the size limits.
+    pub fn attribute_sizes(&self) -> Vec<(String, usize)> {
+        self.attributes
+            .iter()
+            .map(|attr| (attr.key.clone(), attr.key.len() + attr.value.len()))
+            .collect()
+    }
+
+    /// Ensures the attributes take at most `max_total_bytes` bytes (sum of key and value lengths).
+    ///
+    /// If the budget is exceeded, trailing attributes are dropped until the remaining ones plus
+    /// a `truncated=true` marker attribute fit into the budget. Then the marker is appended.
+    /// If the budget is too small for the marker itself, all attributes are removed.
+    pub fn truncate_attributes(&mut self, max_total_bytes: usize) {
+        let size = |attributes: &[Attribute]| -> usize {
+            attributes
+                .iter()
+                .map(|attr| attr.key.len() + attr.value.len())
+                .sum()
+        };
+        if size(&self.attributes) <= max_total_bytes {
+            return;
+        }
+
+        let marker = Attribute {
+            key: "truncated".to_string(),
+            value: "true".to_string(),
+        };
+        let marker_size = marker.key.len() + marker.value.len();
+        if marker_size > max_total_bytes {
+            self.attributes.clear();
+            return;
+        }
+        while !self.attributes.is_empty() && size(&self.attributes) + marker_size > max_total_bytes
+        {
+            self.attributes.pop();
+        }
+        self.attributes.push(marker);
+    }
+
+    /// Sets the data to the 8 byte big endian representation of `value`,
+    /// e.g. to echo a message id back to the caller.
+    /// Use `u64::from_be_bytes` to read it back.
+    pub fn set_data_u64(&mut self, value: u64) {
+        self.set_data(value.to_be_bytes());
+    }
+
+    /// Sets the data to the JSON encoding of `value` and the attribute `data_encoding=json`,
+    /// which tells clients how to read the data.
+    pub fn set_json_data<U: Serialize + ?Sized>(&mut self, value: &U) -> StdResult<()> {
+        self.set_data(to_vec(value)?);
+        self.set_data_encoding("json");
+        Ok(())
+    }
+
+    /// Sets the data and the attribute `data_encoding=raw`, which tells clients
+    /// that the data is opaque bytes.
+    pub fn set_raw_data<U: Into<Binary>>(&mut self, data: U) {
+        self.set_data(data);
+        self.set_data_encoding("raw");
+    }
+
+    /// Sets the `data_encoding` attribute, replacing a previous one
+    fn set_data_encoding(&mut self, encoding: &str) {
+        self.attributes.retain(|attr| attr.key != DATA_ENCODING_KEY);
+        self.add_attribute(DATA_ENCODING_KEY, encoding);
+    }
+
+    /// Returns true if any of the messages matches the predicate.
+    ///
+    /// This is intended for use in test code only.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cosmwasm_std::{coins, BankMsg, CosmosMsg, HumanAddr, Response};
+    /// let mut response: Response = Response::new();
+    /// response.add_message(BankMsg::Send {
+    ///     to_address: HumanAddr::from("recipient"),
+    ///     amount: coins(128, "uint"),
+    /// });
+    /// assert!(response.contains_message(|m| matches!(m, CosmosMsg::Bank(BankMsg::Send { .. }))));
+    /// assert!(!response.contains_message(|m| matches!(m, CosmosMsg::Wasm(_))));
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn contains_message<F>(&self, predicate: F) -> bool
+    where
+        F: Fn(&CosmosMsg<T>) -> bool,
+    {
+        self.messages.iter().any(predicate)
+    }
+
+    /// Sorts the messages by the given key in order to get a canonical execution order.
+    ///
+    /// The sort is stable, i.e. messages with equal keys keep their relative order.
+    pub fn sort_messages_by<K, F>(&mut self, key: F)
+    where
+        K: Ord,
+        F: FnMut(&CosmosMsg<T>) -> K,
+    {
+        self.messages.sort_by_key(key);
+    }
+
+    /// Converts the response into a response with a different custom message type.
+    ///
+    /// `f` is called for every custom message. It returns the converted message or `None`
+    /// to drop it. All other messages, the attributes and the data are kept as they are.
+    pub fn map_custom<U, F>(self, mut f: F) -> Response<U>
+    where
+        U: Clone + fmt::Debug + PartialEq + JsonSchema,
+        F: FnMut(T) -> Option<U>,
+    {
+        let messages = self
+            .messages
+            .into_iter()
+            .filter_map(|msg| match msg {
+                CosmosMsg::Bank(msg) => Some(CosmosMsg::Bank(msg)),
+                CosmosMsg::Custom(msg) => f(msg).map(CosmosMsg::Custom),
+                CosmosMsg::Staking(msg) => Some(CosmosMsg::Staking(msg)),
+                #[cfg(feature = "stargate")]
+                CosmosMsg::Stargate { type_url, value } => {
+                    Some(CosmosMsg::Stargate { type_url, value })
+                }
+                #[cfg(feature = "stargate")]
+                CosmosMsg::Ibc(msg) => Some(CosmosMsg::Ibc(msg)),
+                CosmosMsg::Wasm(msg) => Some(CosmosMsg::Wasm(msg)),
+            })
+            .collect();
+        Response {
+            messages,
+            attributes: self.attributes,
+            data: self.data,
+        }
+    }
+}
+
+impl<T> Response<T>
+where
+    T: Clone + fmt::Debug + PartialEq + JsonSchema + Serialize,
+{
+    /// Serializes the response to a canonical JSON string for snapshot testing.
+    ///
+    /// Attributes are sorted by key and value first, such that two responses which only differ
+    /// in attribute insertion order produce the same output. Messages are kept in order since
+    /// their order is significant for execution.
+    ///
+    /// This is intended for use in test code only.
+    ///
+    /// Panics if the response cannot be serialized.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn to_canonical_json(&self) -> String {
+        let mut canonical = self.clone();
+        canonical
+            .attributes
+            .sort_by(|a, b| a.key.cmp(&b.key).then_with(|| a.value.cmp(&b.value)));
+        let bytes = to_vec(&canonical).expect("Response must be serializable");
+        String::from_utf8(bytes).expect("JSON must be valid UTF-8")
+    }
+
+    /// Lists the differences between two responses, one entry per differing message,
+    /// attribute or data field, e.g. `messages[2] differs: {...} != {...}`. Entries
+    /// that exist in only one of the responses are reported as missing.
+    ///
+    /// This is intended for use in test code only, to make failing assertions on
+    /// responses with many messages legible.
+    ///
+    /// Panics if the response cannot be serialized.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn json_diff(&self, other: &Self) -> Vec<String> {
+        fn json<U: Serialize>(value: &U) -> String {
+            let bytes = to_vec(value).expect("Response must be serializable");
+            String::from_utf8(bytes).expect("JSON must be valid UTF-8")
+        }
+
+        fn diff_lists<U: Serialize>(name: &str, left: &[U], right: &[U], out: &mut Vec<String>) {
+            for index in 0..left.len().max(right.len()) {
+                match (left.get(index), right.get(index)) {
+                    (Some(l), Some(r)) => {
+                        let (l, r) = (json(l), json(r));
+                        if l != r {
+                            out.push(format!("{}[{}] differs: {} != {}", name, index, l, r));
+                        }
+                    }
+                    (Some(l), None) => {
+                        out.push(format!("{}[{}] missing on right: {}", name, index, json(l)))
+                    }
+                    (None, Some(r)) => {
+                        out.push(format!("{}[{}] missing on left: {}", name, index, json(r)))
+                    }
+                    (None, None) => unreachable!(),
+                }
+            }
+        }
+
+        let mut differences = vec![];
+        diff_lists(
+            "messages",
+            &self.messages,
+            &other.messages,
+            &mut differences,
+        );
+        diff_lists(
+            "attributes",
+            &self.attributes,
+            &other.attributes,
+            &mut differences,
+        );
+        let (left, right) = (json(&self.data), json(&other.data));
+        if left != right {
+            differences.push(format!("data differs: {} != {}", left, right));
+        }
+        differences
+    }
+
+    /// Checks that every message can be serialized, which is required to return the
+    /// response to the VM.
+    ///
+    /// This allows a contract to fail with a helpful error instead of failing in the
+    /// entry point's serialization. The error contains the index of the first message
+    /// that could not be serialized.
+    pub fn validate_serializable(&self) -> StdResult<()> {
+        for (index, msg) in self.messages.iter().enumerate() {
+            to_vec(msg).map_err(|mut err| {
+                // only extend the message to keep the source of the error
+                if let StdError::SerializeErr { msg, .. } = &mut err {
+                    *msg = format!("message at index {}: {}", index, msg);
+                }
+                err
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Creates a [`Response`] from optional `messages`, `attributes` and `data` sections,
+/// which must be given in this order.
+///
+/// Messages can be anything that converts into a `CosmosMsg`.
+///
+/// # Examples
+///
+/// ```
+/// # use cosmwasm_std::{attr, coins, BankMsg, Binary, HumanAddr};
+/// use cosmwasm_std::{response, Response};
+///
+/// let res: Response = response! {
+///     messages: [BankMsg::Send {
+///         to_address: HumanAddr::from("recipient"),
+///         amount: coins(128, "uint"),
+///     }],
+///     attributes: [attr("action", "send")],
+///     data: Binary::from(b"the result data"),
+/// };
+/// assert_eq!(res.messages.len(), 1);
+///
+/// let empty: Response = response! {};
+/// assert_eq!(empty, Response::default());
+/// ```
+#[macro_export]
+macro_rules! response {
+    (@fields $response:ident; messages: [$($msg:expr),* $(,)?] $(, $($rest:tt)*)?) => {
+        $( $response.add_message($msg); )*
+        $( $crate::response!(@fields $response; $($rest)*); )?
+    };
+    (@fields $response:ident; attributes: [$($attr:expr),* $(,)?] $(, $($rest:tt)*)?) => {
+        $( $response.attributes.push($attr); )*
+        $( $crate::response!(@fields $response; $($rest)*); )?
+    };
+    (@fields $response:ident; data: $data:expr $(, $($rest:tt)*)?) => {
+        $response.set_data($data);
+        $( $crate::response!(@fields $response; $($rest)*); )?
+    };
+    (@fields $response:ident;) => {};
+    ($($fields:tt)*) => {{
+        #[allow(unused_mut)]
+        let mut response = $crate::Response::default();
+        $crate::response!(@fields response; $($fields)*);
+        response
+    }};
 }
 
 #[cfg(test)]
 mod tests {
-    use super::super::BankMsg;
+    use super::super::{BankMsg, WasmMsg};
     use super::*;
     use crate::addresses::HumanAddr;
-    use crate::{coins, from_slice, to_vec};
+    use crate::{attr, coins, from_slice, to_vec};
 
     #[test]
     fn can_serialize_and_deserialize_init_response() {
@@ -140,4 +583,599 @@ mod tests {
         let deserialized: Response = from_slice(&serialized).expect("decode contract result");
         assert_eq!(deserialized, original);
     }
+
+    #[test]
+    fn can_deserialize_legacy_response() {
+        // the format of the 0.13 release
+        let legacy = br#"{"messages":[{"bank":{"send":{"to_address":"you","amount":[{"denom":"earth","amount":"1015"}]}}}],"attributes":[{"key":"action","value":"release"}],"data":"qrs="}"#;
+        let deserialized: Response = from_slice(legacy).unwrap();
+        assert_eq!(
+            deserialized,
+            Response {
+                messages: vec![BankMsg::Send {
+                    to_address: HumanAddr::from("you"),
+                    amount: coins(1015, "earth"),
+                }
+                .into()],
+                attributes: vec![attr("action", "release")],
+                data: Some(Binary::from([0xAA, 0xBB])),
+            }
+        );
+
+        // data may be omitted
+        let deserialized: Response = from_slice(br#"{"messages":[],"attributes":[]}"#).unwrap();
+        assert_eq!(deserialized, Response::default());
+    }
+
+    #[test]
+    fn contains_message_works() {
+        let mut response: Response = Response::new();
+        assert!(!response.contains_message(|_| true));
+
+        response.add_message(BankMsg::Send {
+            to_address: HumanAddr::from("you"),
+            amount: coins(1015, "earth"),
+        });
+        response.add_message(WasmMsg::ClearAdmin {
+            contract_addr: HumanAddr::from("contract"),
+        });
+        assert!(response.contains_message(|m| matches!(m, CosmosMsg::Bank(BankMsg::Send { .. }))));
+        assert!(response.contains_message(|m| matches!(
+            m,
+            CosmosMsg::Wasm(WasmMsg::ClearAdmin { contract_addr }) if contract_addr == "contract"
+        )));
+        assert!(!response.contains_message(|m| matches!(m, CosmosMsg::Staking(_))));
+    }
+
+    #[test]
+    fn add_message_if_works() {
+        let mut response: Response = Response::new();
+        response.add_message_if(
+            false,
+            BankMsg::Send {
+                to_address: HumanAddr::from("you"),
+                amount: coins(1015, "earth"),
+            },
+        );
+        assert!(response.messages.is_empty());
+
+        response.add_message_if(
+            true,
+            WasmMsg::ClearAdmin {
+                contract_addr: HumanAddr::from("contract"),
+            },
+        );
+        assert_eq!(
+            response.messages,
+            vec![WasmMsg::ClearAdmin {
+                contract_addr: HumanAddr::from("contract"),
+            }
+            .into()]
+        );
+    }
+
+    #[test]
+    fn extend_appends_messages() {
+        let mut response: Response = Response::new();
+        response.add_message(WasmMsg::ClearAdmin {
+            contract_addr: HumanAddr::from("first"),
+        });
+        response.extend(vec![
+            WasmMsg::ClearAdmin {
+                contract_addr: HumanAddr::from("second"),
+            }
+            .into(),
+            BankMsg::Send {
+                to_address: HumanAddr::from("third"),
+                amount: coins(1015, "earth"),
+            }
+            .into(),
+        ]);
+        response.extend(std::iter::empty());
+
+        assert_eq!(
+            response.messages,
+            vec![
+                WasmMsg::ClearAdmin {
+                    contract_addr: HumanAddr::from("first"),
+                }
+                .into(),
+                WasmMsg::ClearAdmin {
+                    contract_addr: HumanAddr::from("second"),
+                }
+                .into(),
+                BankMsg::Send {
+                    to_address: HumanAddr::from("third"),
+                    amount: coins(1015, "earth"),
+                }
+                .into(),
+            ]
+        );
+    }
+
+    #[test]
+    fn normalize_attribute_keys_works() {
+        let mut response: Response = Response::new();
+        response.add_attribute("Action", "Reflect");
+        response.add_attribute("OWNER", "CreatorAddr");
+        response.add_attribute("amount", "12ucosm");
+
+        response.normalize_attribute_keys();
+        assert_eq!(
+            response.attributes,
+            vec![
+                attr("action", "Reflect"),
+                attr("owner", "CreatorAddr"),
+                attr("amount", "12ucosm"),
+            ]
+        );
+    }
+
+    #[test]
+    fn strip_empty_attributes_works() {
+        let mut response: Response = Response {
+            attributes: vec![
+                attr("action", "reflect"),
+                attr("memo", ""),
+                attr("", "no key"),
+                attr("sender", "creator"),
+                attr("note", ""),
+            ],
+            ..Response::default()
+        };
+        response.strip_empty_attributes();
+        assert_eq!(
+            response.attributes,
+            vec![
+                attr("action", "reflect"),
+                attr("", "no key"),
+                attr("sender", "creator"),
+            ]
+        );
+    }
+
+    #[test]
+    fn truncate_attributes_works() {
+        let mut response: Response = Response::new();
+        response.add_attribute("action", "reflect"); // 13 bytes
+        response.add_attribute("owner", "creator"); // 12 bytes
+        response.add_attribute("amount", "12ucosm"); // 13 bytes
+
+        // within budget
+        response.truncate_attributes(38);
+        assert_eq!(response.attributes.len(), 3);
+
+        // exceeding budget
+        response.truncate_attributes(30);
+        assert_eq!(
+            response.attributes,
+            vec![attr("action", "reflect"), attr("truncated", "true")]
+        );
+
+        // only room for the marker
+        response.truncate_attributes(13);
+        assert_eq!(response.attributes, vec![attr("truncated", "true")]);
+    }
+
+    #[test]
+    fn truncate_attributes_without_room_for_marker() {
+        let mut response: Response = Response::new();
+        response.add_attribute("action", "reflect");
+        response.add_attribute("owner", "creator");
+
+        response.truncate_attributes(12);
+        assert!(response.attributes.is_empty());
+
+        response.add_attribute("action", "reflect");
+        response.truncate_attributes(0);
+        assert!(response.attributes.is_empty());
+    }
+
+    #[test]
+    fn take_data_works() {
+        let mut response: Response = Response::new();
+        assert_eq!(response.take_data(), None);
+
+        response.set_data(b"the data");
+        assert_eq!(response.take_data(), Some(Binary::from(b"the data")));
+        assert_eq!(response.take_data(), None);
+        assert_eq!(response.data, None);
+    }
+
+    #[test]
+    fn set_data_u64_works() {
+        let mut response: Response = Response::new();
+        response.set_data_u64(0x0102030405060708);
+        let data = response.data.unwrap();
+        assert_eq!(data.as_slice(), [1, 2, 3, 4, 5, 6, 7, 8]);
+
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(data.as_slice());
+        assert_eq!(u64::from_be_bytes(bytes), 0x0102030405060708);
+    }
+
+    #[test]
+    fn from_reply_data_works() {
+        let response: Response = Response::from_reply_data(Some(Binary::from(b"foo".to_vec())));
+        assert!(response.messages.is_empty());
+        assert_eq!(response.attributes, vec![attr("action", "reply")]);
+        assert_eq!(response.data, Some(Binary::from(b"foo".to_vec())));
+
+        let response: Response = Response::from_reply_data(None);
+        assert!(response.messages.is_empty());
+        assert_eq!(response.attributes, vec![attr("action", "reply")]);
+        assert_eq!(response.data, None);
+    }
+
+    #[test]
+    fn set_json_data_and_set_raw_data_work() {
+        #[derive(Serialize)]
+        struct Data {
+            id: u32,
+        }
+
+        let mut response: Response = Response::new();
+        response.add_attribute("action", "reflect");
+        response.set_json_data(&Data { id: 42 }).unwrap();
+        assert_eq!(response.data, Some(Binary::from(br#"{"id":42}"#.to_vec())));
+        assert_eq!(
+            response.attributes,
+            vec![attr("action", "reflect"), attr("data_encoding", "json")]
+        );
+
+        // the encoding attribute is replaced when setting data again
+        response.set_raw_data(vec![0u8, 1]);
+        assert_eq!(response.data, Some(Binary::from(vec![0u8, 1])));
+        assert_eq!(
+            response.attributes,
+            vec![attr("action", "reflect"), attr("data_encoding", "raw")]
+        );
+    }
+
+    #[test]
+    fn sort_messages_by_works() {
+        let send = |to: &str| -> CosmosMsg {
+            BankMsg::Send {
+                to_address: HumanAddr::from(to),
+                amount: coins(1, "earth"),
+            }
+            .into()
+        };
+        let recipient = |msg: &CosmosMsg| match msg {
+            CosmosMsg::Bank(BankMsg::Send { to_address, .. }) => to_address.to_string(),
+            _ => String::new(),
+        };
+
+        let mut response = Response::new();
+        response.add_message(send("carol"));
+        response.add_message(send("alice"));
+        response.add_message(send("bob"));
+
+        response.sort_messages_by(recipient);
+        assert_eq!(
+            response.messages,
+            vec![send("alice"), send("bob"), send("carol")]
+        );
+    }
+
+    #[test]
+    fn response_macro_works() {
+        let from_macro: Response = crate::response! {
+            messages: [
+                BankMsg::Send {
+                    to_address: HumanAddr::from("you"),
+                    amount: coins(1015, "earth"),
+                },
+                BankMsg::Send {
+                    to_address: HumanAddr::from("me"),
+                    amount: coins(7, "moon"),
+                },
+            ],
+            attributes: [attr("action", "release")],
+            data: Binary::from([0xAA, 0xBB]),
+        };
+        let expected = Response {
+            messages: vec![
+                BankMsg::Send {
+                    to_address: HumanAddr::from("you"),
+                    amount: coins(1015, "earth"),
+                }
+                .into(),
+                BankMsg::Send {
+                    to_address: HumanAddr::from("me"),
+                    amount: coins(7, "moon"),
+                }
+                .into(),
+            ],
+            attributes: vec![attr("action", "release")],
+            data: Some(Binary::from([0xAA, 0xBB])),
+        };
+        assert_eq!(from_macro, expected);
+
+        // all sections are optional
+        let from_macro: Response = crate::response! { attributes: [attr("action", "release")] };
+        let expected = Response {
+            attributes: vec![attr("action", "release")],
+            ..Response::default()
+        };
+        assert_eq!(from_macro, expected);
+
+        let from_macro: Response = crate::response! {};
+        assert_eq!(from_macro, Response::default());
+    }
+
+    #[test]
+    fn with_messages_works() {
+        let messages: Vec<CosmosMsg> = vec![
+            BankMsg::Send {
+                to_address: HumanAddr::from("you"),
+                amount: coins(1015, "earth"),
+            }
+            .into(),
+            BankMsg::Send {
+                to_address: HumanAddr::from("me"),
+                amount: coins(7, "moon"),
+            }
+            .into(),
+        ];
+        let expected = Response {
+            messages: messages.clone(),
+            attributes: vec![],
+            data: None,
+        };
+        assert_eq!(Response::with_messages(messages), expected);
+    }
+
+    #[test]
+    fn to_canonical_json_ignores_attribute_order() {
+        let messages: Vec<CosmosMsg> = vec![
+            BankMsg::Send {
+                to_address: HumanAddr::from("you"),
+                amount: coins(1015, "earth"),
+            }
+            .into(),
+            WasmMsg::Execute {
+                contract_addr: HumanAddr::from("contract"),
+                msg: Binary::from(br#"{"do":"something"}"#),
+                send: vec![],
+            }
+            .into(),
+        ];
+        let mut first = Response::with_messages(messages.clone());
+        first.add_attribute("action", "reflect");
+        first.add_attribute("sender", "creator");
+        first.add_attribute("action", "again");
+        first.set_data(b"result");
+        let mut second = Response::with_messages(messages);
+        second.add_attribute("sender", "creator");
+        second.add_attribute("action", "again");
+        second.add_attribute("action", "reflect");
+        second.set_data(b"result");
+        assert_ne!(first, second);
+        assert_eq!(first.to_canonical_json(), second.to_canonical_json());
+        assert_eq!(
+            first.to_canonical_json(),
+            r#"{"messages":[{"bank":{"send":{"to_address":"you","amount":[{"denom":"earth","amount":"1015"}]}}},{"wasm":{"execute":{"contract_addr":"contract","msg":"eyJkbyI6InNvbWV0aGluZyJ9","send":[]}}}],"attributes":[{"key":"action","value":"again"},{"key":"action","value":"reflect"},{"key":"sender","value":"creator"}],"data":"cmVzdWx0"}"#
+        );
+
+        // message order is significant and preserved
+        let mut reordered = first.clone();
+        reordered.messages.reverse();
+        assert_ne!(first.to_canonical_json(), reordered.to_canonical_json());
+    }
+
+    #[test]
+    fn map_custom_works() {
+        let send = BankMsg::Send {
+            to_address: HumanAddr::from("you"),
+            amount: coins(1015, "earth"),
+        };
+        let response: Response<String> = Response {
+            messages: vec![
+                CosmosMsg::Custom("keep".to_string()),
+                send.clone().into(),
+                CosmosMsg::Custom("drop".to_string()),
+            ],
+            attributes: vec![attr("action", "reflect")],
+            data: Some(Binary::from(b"result")),
+        };
+
+        // transform
+        let mapped: Response<u64> = response.clone().map_custom(|msg| Some(msg.len() as u64));
+        assert_eq!(
+            mapped,
+            Response {
+                messages: vec![
+                    CosmosMsg::Custom(4),
+                    send.clone().into(),
+                    CosmosMsg::Custom(4)
+                ],
+                attributes: vec![attr("action", "reflect")],
+                data: Some(Binary::from(b"result")),
+            }
+        );
+
+        // drop some
+        let mapped: Response<String> =
+            response
+                .clone()
+                .map_custom(|msg| if msg == "keep" { Some(msg) } else { None });
+        assert_eq!(
+            mapped.messages,
+            vec![CosmosMsg::Custom("keep".to_string()), send.clone().into()]
+        );
+
+        // drop all
+        let mapped: Response = response.map_custom(|_| None);
+        assert_eq!(mapped.messages, vec![CosmosMsg::Bank(send)]);
+        assert_eq!(mapped.attributes, vec![attr("action", "reflect")]);
+        assert_eq!(mapped.data, Some(Binary::from(b"result")));
+    }
+
+    #[test]
+    fn attribute_lookup_works() {
+        let mut response: Response = Response::new();
+        response.add_attribute("action", "reflect");
+        response.add_attribute("recipient", "alice");
+        response.add_attribute("recipient", "bob");
+
+        // present
+        assert_eq!(response.attribute("action"), Some("reflect"));
+        assert_eq!(response.attributes_for("action"), vec!["reflect"]);
+
+        // absent
+        assert_eq!(response.attribute("amount"), None);
+        assert_eq!(response.attributes_for("amount"), Vec::<&str>::new());
+
+        // duplicate keys
+        assert_eq!(response.attribute("recipient"), Some("alice"));
+        assert_eq!(response.attributes_for("recipient"), vec!["alice", "bob"]);
+    }
+
+    #[test]
+    fn attributes_as_map_works() {
+        let mut response: Response = Response::new();
+        assert_eq!(response.attributes_as_map(), BTreeMap::new());
+
+        response.add_attribute("action", "reflect");
+        response.add_attribute("recipient", "alice");
+        response.add_attribute("recipient", "bob");
+        response.add_attribute("amount", "123");
+        response.add_attribute("recipient", "alice");
+
+        let map = response.attributes_as_map();
+        let mut expected = BTreeMap::new();
+        expected.insert("action".to_string(), vec!["reflect".to_string()]);
+        expected.insert("amount".to_string(), vec!["123".to_string()]);
+        expected.insert(
+            "recipient".to_string(),
+            vec!["alice".to_string(), "bob".to_string(), "alice".to_string()],
+        );
+        assert_eq!(map, expected);
+    }
+
+    #[test]
+    fn require_unique_keys_works() {
+        let response: Response = Response {
+            attributes: vec![attr("action", "reflect"), attr("sender", "creator")],
+            ..Response::default()
+        };
+        response.require_unique_keys().unwrap();
+
+        let response: Response = Response {
+            attributes: vec![
+                attr("amount", "1"),
+                attr("action", "reflect"),
+                attr("amount", "2"),
+                attr("sender", "creator"),
+                attr("action", "transfer"),
+                attr("amount", "3"),
+            ],
+            ..Response::default()
+        };
+        match response.require_unique_keys().unwrap_err() {
+            StdError::GenericErr { msg, .. } => {
+                assert_eq!(msg, "Duplicate attribute keys: amount, action")
+            }
+            e => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn attribute_sizes_works() {
+        let mut response: Response = Response::new();
+        assert_eq!(response.attribute_sizes(), vec![]);
+
+        response.add_attribute("action", "reflect");
+        response.add_attribute("memo", "");
+        response.add_attribute("emoji", "\u{1F600}");
+        response.add_attribute("action", "reflect");
+        assert_eq!(
+            response.attribute_sizes(),
+            vec![
+                ("action".to_string(), 13),
+                ("memo".to_string(), 4),
+                // sizes are counted in bytes, not chars
+                ("emoji".to_string(), 9),
+                ("action".to_string(), 13),
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_serializable_works() {
+        use std::error::Error;
+
+        #[derive(Clone, Debug, PartialEq, JsonSchema)]
+        struct Unserializable {}
+
+        impl Serialize for Unserializable {
+            fn serialize<S: serde::Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+                Err(serde::ser::Error::custom("this cannot be serialized"))
+            }
+        }
+
+        let mut response: Response<Unserializable> = Response::new();
+        response.validate_serializable().unwrap();
+
+        response.add_message(BankMsg::Send {
+            to_address: HumanAddr::from("recipient"),
+            amount: coins(123, "ucosm"),
+        });
+        response.validate_serializable().unwrap();
+
+        response.add_message(CosmosMsg::Custom(Unserializable {}));
+        let err = response.validate_serializable().unwrap_err();
+        assert!(err.source().is_some());
+        match err {
+            StdError::SerializeErr {
+                source_type, msg, ..
+            } => {
+                assert!(source_type.contains("CosmosMsg"));
+                assert!(msg.starts_with("message at index 1: "));
+            }
+            e => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn json_diff_works() {
+        let send = |to: &str, amount: u128| -> CosmosMsg {
+            BankMsg::Send {
+                to_address: HumanAddr::from(to),
+                amount: coins(amount, "ucosm"),
+            }
+            .into()
+        };
+
+        let mut left: Response = Response::new();
+        left.add_message(send("alice", 1));
+        left.add_message(send("bob", 2));
+        left.add_message(send("carl", 3));
+        left.add_attribute("action", "reflect");
+        let right = left.clone();
+        assert_eq!(left.json_diff(&right), Vec::<String>::new());
+
+        let mut right = left.clone();
+        right.messages[2] = send("carl", 4);
+        assert_eq!(
+            left.json_diff(&right),
+            vec![
+                r#"messages[2] differs: {"bank":{"send":{"to_address":"carl","amount":[{"denom":"ucosm","amount":"3"}]}}} != {"bank":{"send":{"to_address":"carl","amount":[{"denom":"ucosm","amount":"4"}]}}}"#
+            ]
+        );
+
+        let mut right = left.clone();
+        right.messages.pop();
+        right.add_attribute("extra", "yes");
+        right.set_data(b"hi");
+        assert_eq!(
+            left.json_diff(&right),
+            vec![
+                r#"messages[2] missing on right: {"bank":{"send":{"to_address":"carl","amount":[{"denom":"ucosm","amount":"3"}]}}}"#,
+                r#"attributes[1] missing on left: {"key":"extra","value":"yes"}"#,
+                r#"data differs: null != "aGk=""#,
+            ]
+        );
+    }
 }
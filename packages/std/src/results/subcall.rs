@@ -0,0 +1,99 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::binary::Binary;
+
+use super::{Attribute, ContractResult};
+
+/// A full [*Cosmos SDK* event] as emitted by a message execution.
+///
+/// [*Cosmos SDK* event]: https://docs.cosmos.network/v0.42/core/events.html
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Event {
+    /// The event type. This is renamed to "kind" because "type" is reserved in Rust.
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub attributes: Vec<Attribute>,
+}
+
+impl Event {
+    /// Creates a new event with the given type and no attributes
+    pub fn new<K: Into<String>>(kind: K) -> Self {
+        Event {
+            kind: kind.into(),
+            attributes: vec![],
+        }
+    }
+}
+
+/// The result of a submessage execution that is passed back to the contract.
+///
+/// Contracts that dispatch submessages receive this in the `reply` entry point,
+/// which is expected to have the following signature:
+///
+/// ```
+/// # use cosmwasm_std::{DepsMut, Env, Reply, Response, StdResult};
+/// pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
+///     // ...
+/// #   Ok(Response::default())
+/// }
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Reply {
+    /// The id that was set by the contract when dispatching the submessage
+    pub id: u64,
+    pub result: ContractResult<SubMsgExecutionResponse>,
+}
+
+/// The information we get back from a successful submessage execution
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SubMsgExecutionResponse {
+    pub events: Vec<Event>,
+    pub data: Option<Binary>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{attr, from_slice, to_vec};
+
+    #[test]
+    fn event_serializes_kind_as_type() {
+        let event = Event {
+            kind: "wasm".to_string(),
+            attributes: vec![attr("action", "reflect")],
+        };
+        let serialized = to_vec(&event).unwrap();
+        assert_eq!(
+            serialized,
+            br#"{"type":"wasm","attributes":[{"key":"action","value":"reflect"}]}"#
+        );
+        let deserialized: Event = from_slice(&serialized).unwrap();
+        assert_eq!(deserialized, event);
+    }
+
+    #[test]
+    fn reply_serialization_works() {
+        let reply = Reply {
+            id: 42,
+            result: ContractResult::Ok(SubMsgExecutionResponse {
+                events: vec![Event::new("message")],
+                data: Some(Binary::from(b"hi")),
+            }),
+        };
+        let serialized = to_vec(&reply).unwrap();
+        assert_eq!(
+            serialized,
+            br#"{"id":42,"result":{"ok":{"events":[{"type":"message","attributes":[]}],"data":"aGk="}}}"#
+        );
+        let deserialized: Reply = from_slice(&serialized).unwrap();
+        assert_eq!(deserialized, reply);
+
+        let reply = Reply {
+            id: 43,
+            result: ContractResult::Err("out of gas".to_string()),
+        };
+        let serialized = to_vec(&reply).unwrap();
+        assert_eq!(serialized, br#"{"id":43,"result":{"error":"out of gas"}}"#);
+    }
+}
@@ -2,16 +2,25 @@
 // The reason is two fold:
 // 1. To easily ensure that all calling libraries use the same version (minimize code size)
 // 2. To allow us to switch out to eg. serde-json-core more easily
-use serde::{de::DeserializeOwned, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::any::type_name;
 
 use crate::binary::Binary;
 use crate::errors::{StdError, StdResult};
 
+/// Deserializes `value` as JSON. This is strict: trailing content after the JSON value
+/// other than whitespace results in an error.
 pub fn from_slice<T: DeserializeOwned>(value: &[u8]) -> StdResult<T> {
-    serde_json_wasm::from_slice(value).map_err(|e| StdError::parse_err(type_name::<T>(), e))
+    serde_json_wasm::from_slice(value).map_err(|e| StdError::parse_err_from(type_name::<T>(), e))
 }
 
+/// Like `from_slice` but allows `T` to borrow from `value`, e.g. `&str` fields,
+/// which avoids copying large string data when decoding.
+pub fn from_slice_borrowed<'a, T: Deserialize<'a>>(value: &'a [u8]) -> StdResult<T> {
+    serde_json_wasm::from_slice(value).map_err(|e| StdError::parse_err_from(type_name::<T>(), e))
+}
+
+/// Deserializes the JSON in `value`. Like `from_slice`, this errors on trailing content.
 pub fn from_binary<T: DeserializeOwned>(value: &Binary) -> StdResult<T> {
     from_slice(value.as_slice())
 }
@@ -20,7 +29,7 @@ pub fn to_vec<T>(data: &T) -> StdResult<Vec<u8>>
 where
     T: Serialize + ?Sized,
 {
-    serde_json_wasm::to_vec(data).map_err(|e| StdError::serialize_err(type_name::<T>(), e))
+    serde_json_wasm::to_vec(data).map_err(|e| StdError::serialize_err_from(type_name::<T>(), e))
 }
 
 pub fn to_binary<T>(data: &T) -> StdResult<Binary>
@@ -89,6 +98,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn from_slice_borrowed_works() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Borrowed<'a> {
+            name: &'a str,
+            amount: u32,
+        }
+
+        let data = br#"{"name":"cosmwasm","amount":42}"#;
+        let deserialized: Borrowed = from_slice_borrowed(data).unwrap();
+        assert_eq!(
+            deserialized,
+            Borrowed {
+                name: "cosmwasm",
+                amount: 42
+            }
+        );
+
+        // the string is not copied but points into the input
+        let start = data.as_ptr() as usize;
+        let name = deserialized.name.as_ptr() as usize;
+        assert!(name >= start && name < start + data.len());
+
+        // owned types work as well
+        let deserialized: SomeMsg = from_slice_borrowed(br#"{"refund":{}}"#).unwrap();
+        assert_eq!(deserialized, SomeMsg::Refund {});
+    }
+
     #[test]
     fn from_slice_or_binary() {
         let msg = SomeMsg::Refund {};
@@ -101,6 +138,30 @@ mod tests {
         assert_eq!(parse_slice, msg);
     }
 
+    #[test]
+    fn from_slice_rejects_trailing_data() {
+        // trailing whitespace is fine
+        let deserialized: SomeMsg = from_slice(b"{\"refund\":{}} \n").unwrap();
+        assert_eq!(deserialized, SomeMsg::Refund {});
+
+        // valid JSON followed by garbage is not
+        let data = br#"{"refund":{}}{"cowsay":{"text":"moo"}}"#;
+        match from_slice::<SomeMsg>(data).unwrap_err() {
+            StdError::ParseErr { target_type, .. } => {
+                assert_eq!(target_type, type_name::<SomeMsg>())
+            }
+            e => panic!("Unexpected error: {:?}", e),
+        }
+        match from_binary::<SomeMsg>(&Binary::from(&data[..])).unwrap_err() {
+            StdError::ParseErr { .. } => {}
+            e => panic!("Unexpected error: {:?}", e),
+        }
+        match from_slice_borrowed::<SomeMsg>(b"{\"refund\":{}}xyz").unwrap_err() {
+            StdError::ParseErr { .. } => {}
+            e => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
     #[test]
     fn to_vec_works_for_special_chars() {
         let msg = SomeMsg::Cowsay {
@@ -1,4 +1,6 @@
 use serde::{de::DeserializeOwned, Serialize};
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::ops::Deref;
 
 use crate::addresses::{CanonicalAddr, HumanAddr};
@@ -8,7 +10,7 @@ use crate::errors::{StdError, StdResult};
 #[cfg(feature = "iterator")]
 use crate::iterator::{Order, KV};
 use crate::query::{
-    AllBalanceResponse, BalanceResponse, BankQuery, CustomQuery, QueryRequest, WasmQuery,
+    wasm, AllBalanceResponse, BalanceResponse, BankQuery, CustomQuery, QueryRequest,
 };
 #[cfg(feature = "staking")]
 use crate::query::{
@@ -16,7 +18,7 @@ use crate::query::{
     StakingQuery, Validator, ValidatorsResponse,
 };
 use crate::results::{ContractResult, Empty, SystemResult};
-use crate::serde::{from_binary, to_binary, to_vec};
+use crate::serde::{from_binary, from_slice, to_vec};
 
 /// Storage provides read and write access to a persistent storage.
 /// If you only want to provide read access, provide `&Storage`
@@ -66,6 +68,27 @@ pub trait Storage {
 pub trait Api {
     fn canonical_address(&self, human: &HumanAddr) -> StdResult<CanonicalAddr>;
     fn human_address(&self, canonical: &CanonicalAddr) -> StdResult<HumanAddr>;
+    /// Validates `human` and converts it to its canonical form in one call.
+    ///
+    /// In addition to the checks of `canonical_address`, this ensures converting the result back
+    /// yields `human` again, which rejects e.g. addresses that are not normalized.
+    /// All failures are reported as `StdError::InvalidAddress`.
+    fn addr_validate_to_canonical(&self, human: &HumanAddr) -> StdResult<CanonicalAddr> {
+        let canonical = self.canonical_address(human).map_err(|err| {
+            let msg = match err {
+                StdError::GenericErr { msg, .. } => msg,
+                err => err.to_string(),
+            };
+            StdError::invalid_address(human, msg)
+        })?;
+        match self.human_address(&canonical) {
+            Ok(recovered) if recovered == *human => Ok(canonical),
+            _ => Err(StdError::invalid_address(
+                human,
+                "Address does not round-trip",
+            )),
+        }
+    }
     /// Emits a debugging message that is handled depending on the environment (typically printed to console or ignored).
     /// Those messages are not persisted to chain.
     fn debug(&self, message: &str);
@@ -83,6 +106,49 @@ pub trait Querier {
     fn raw_query(&self, bin_request: &[u8]) -> QuerierResult;
 }
 
+/// A querier that memoizes the results of another querier by the raw request bytes.
+///
+/// This is opt-in and intended to be created at the beginning of an entry point call
+/// and dropped at its end, such that repeated identical queries within one call are only
+/// sent to the chain once.
+///
+/// # Examples
+///
+/// ```
+/// # use cosmwasm_std::testing::mock_dependencies;
+/// use cosmwasm_std::{CachingQuerier, QuerierWrapper};
+///
+/// # let deps = mock_dependencies(&[]);
+/// let cache = CachingQuerier::new(&deps.querier);
+/// let querier = QuerierWrapper::new(&cache);
+/// ```
+pub struct CachingQuerier<'a> {
+    querier: &'a dyn Querier,
+    cache: RefCell<HashMap<Vec<u8>, QuerierResult>>,
+}
+
+impl<'a> CachingQuerier<'a> {
+    pub fn new(querier: &'a dyn Querier) -> Self {
+        CachingQuerier {
+            querier,
+            cache: RefCell::new(HashMap::new()),
+        }
+    }
+}
+
+impl<'a> Querier for CachingQuerier<'a> {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        if let Some(result) = self.cache.borrow().get(bin_request) {
+            return result.clone();
+        }
+        let result = self.querier.raw_query(bin_request);
+        self.cache
+            .borrow_mut()
+            .insert(bin_request.to_vec(), result.clone());
+        result
+    }
+}
+
 #[derive(Copy, Clone)]
 pub struct QuerierWrapper<'a>(&'a dyn Querier);
 
@@ -113,7 +179,9 @@ impl<'a> QuerierWrapper<'a> {
     ///
     /// Any error (System Error, Error or called contract, or Parse Error) are flattened into
     /// one level. Only use this if you don't need to check the SystemError
-    /// eg. If you don't differentiate between contract missing and contract returned error
+    /// eg. If you don't differentiate between contract missing and contract returned error.
+    /// System errors are prefixed with "Querier system error" and contract errors with
+    /// "Querier contract error" in the resulting `StdError::GenericErr`.
     pub fn custom_query<C: CustomQuery, U: DeserializeOwned>(
         &self,
         request: &QueryRequest<C>,
@@ -159,11 +227,7 @@ impl<'a> QuerierWrapper<'a> {
         contract: V,
         msg: &U,
     ) -> StdResult<T> {
-        let request = WasmQuery::Smart {
-            contract_addr: contract.into(),
-            msg: to_binary(msg)?,
-        }
-        .into();
+        let request = wasm::smart(contract, msg)?;
         self.query(&request)
     }
 
@@ -179,11 +243,7 @@ impl<'a> QuerierWrapper<'a> {
         contract: T,
         key: U,
     ) -> StdResult<Option<Vec<u8>>> {
-        let request: QueryRequest<Empty> = WasmQuery::Raw {
-            contract_addr: contract.into(),
-            key: key.into(),
-        }
-        .into();
+        let request: QueryRequest<Empty> = wasm::raw(contract, key);
         // we cannot use query, as it will try to parse the binary data, when we just want to return it,
         // so a bit of code copy here...
         let raw = to_vec(&request).map_err(|serialize_err| {
@@ -207,6 +267,19 @@ impl<'a> QuerierWrapper<'a> {
         }
     }
 
+    /// Like `query_wasm_raw`, but decodes the value from JSON, e.g. a struct stored by the other
+    /// contract. Returns `None` if there is no value for the key.
+    pub fn query_wasm_raw_typed<T: DeserializeOwned, C: Into<HumanAddr>, K: Into<Binary>>(
+        &self,
+        contract: C,
+        key: K,
+    ) -> StdResult<Option<T>> {
+        match self.query_wasm_raw(contract, key)? {
+            Some(value) => from_slice(&value).map(Some),
+            None => Ok(None),
+        }
+    }
+
     #[cfg(feature = "staking")]
     pub fn query_validators(&self) -> StdResult<Vec<Validator>> {
         let request = StakingQuery::Validators {}.into();
@@ -254,7 +327,8 @@ impl<'a> QuerierWrapper<'a> {
 mod tests {
     use super::*;
     use crate::mock::MockQuerier;
-    use crate::{coins, from_slice, Uint128};
+    use crate::{coins, Uint128, WasmQuery};
+    use serde::Deserialize;
 
     // this is a simple demo helper to prove we can use it
     fn demo_helper(_querier: &dyn Querier) -> u64 {
@@ -276,6 +350,89 @@ mod tests {
         assert_eq!(2, res);
     }
 
+    struct CountingQuerier {
+        inner: MockQuerier<Empty>,
+        calls: std::cell::Cell<usize>,
+    }
+
+    impl Querier for CountingQuerier {
+        fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+            self.calls.set(self.calls.get() + 1);
+            self.inner.raw_query(bin_request)
+        }
+    }
+
+    #[test]
+    fn caching_querier_caches_identical_requests() {
+        let acct = HumanAddr::from("foobar");
+        let counting = CountingQuerier {
+            inner: MockQuerier::new(&[(&acct, &coins(5, "BTC"))]),
+            calls: std::cell::Cell::new(0),
+        };
+        let cache = CachingQuerier::new(&counting);
+        let wrapper = QuerierWrapper::new(&cache);
+
+        let balance = wrapper.query_balance(&acct, "BTC").unwrap();
+        assert_eq!(balance.amount, Uint128(5));
+        let balance = wrapper.query_balance(&acct, "BTC").unwrap();
+        assert_eq!(balance.amount, Uint128(5));
+        assert_eq!(counting.calls.get(), 1);
+
+        // a different request is not served from the cache
+        let balances = wrapper.query_all_balances(&acct).unwrap();
+        assert_eq!(balances, coins(5, "BTC"));
+        assert_eq!(counting.calls.get(), 2);
+    }
+
+    #[test]
+    fn custom_query_works() {
+        let acct = HumanAddr::from("foobar");
+        let querier: MockQuerier<Empty> = MockQuerier::new(&[(&acct, &coins(5, "BTC"))]);
+        let wrapper = QuerierWrapper::new(&querier);
+        let query = QueryRequest::<Empty>::Bank(BankQuery::Balance {
+            address: acct,
+            denom: "BTC".to_string(),
+        });
+
+        let balance: BalanceResponse = wrapper.custom_query(&query).unwrap();
+        assert_eq!(balance.amount.amount, Uint128(5));
+    }
+
+    #[test]
+    fn custom_query_maps_system_error() {
+        // the default custom handler of the mock returns a system error
+        let querier: MockQuerier<Empty> = MockQuerier::new(&[]);
+        let wrapper = QuerierWrapper::new(&querier);
+
+        let err = wrapper
+            .custom_query::<_, Empty>(&QueryRequest::Custom(Empty {}))
+            .unwrap_err();
+        match err {
+            StdError::GenericErr { msg, .. } => {
+                assert_eq!(msg, "Querier system error: Unsupported query type: custom")
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn custom_query_maps_contract_error() {
+        let querier: MockQuerier<Empty> = MockQuerier::new(&[]).with_custom_handler(|_| {
+            SystemResult::Ok(ContractResult::Err("Something went wrong".to_string()))
+        });
+        let wrapper = QuerierWrapper::new(&querier);
+
+        let err = wrapper
+            .custom_query::<_, Empty>(&QueryRequest::Custom(Empty {}))
+            .unwrap_err();
+        match err {
+            StdError::GenericErr { msg, .. } => {
+                assert_eq!(msg, "Querier contract error: Something went wrong")
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+
     #[test]
     fn auto_deref_raw_query() {
         let acct = HumanAddr::from("foobar");
@@ -293,4 +450,63 @@ mod tests {
         let balance: BalanceResponse = from_slice(&raw).unwrap();
         assert_eq!(balance.amount.amount, Uint128(5));
     }
+
+    #[test]
+    fn query_wasm_raw_typed_works() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Config {
+            owner: HumanAddr,
+            count: u32,
+        }
+
+        /// Answers raw queries of contract "other", storing a `Config` under key "config"
+        struct RawQuerier {}
+
+        impl Querier for RawQuerier {
+            fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+                let request: QueryRequest<Empty> = from_slice(bin_request).unwrap();
+                let value = match request {
+                    QueryRequest::Wasm(WasmQuery::Raw { contract_addr, key })
+                        if contract_addr == "other" && key.as_slice() == b"config" =>
+                    {
+                        to_vec(&Config {
+                            owner: HumanAddr::from("creator"),
+                            count: 7,
+                        })
+                        .unwrap()
+                    }
+                    _ => vec![],
+                };
+                SystemResult::Ok(ContractResult::Ok(Binary::from(value)))
+            }
+        }
+
+        let querier = RawQuerier {};
+        let wrapper = QuerierWrapper::new(&querier);
+        let config: Option<Config> = wrapper
+            .query_wasm_raw_typed("other", Binary::from(b"config"))
+            .unwrap();
+        assert_eq!(
+            config,
+            Some(Config {
+                owner: HumanAddr::from("creator"),
+                count: 7,
+            })
+        );
+
+        // missing value
+        let config: Option<Config> = wrapper
+            .query_wasm_raw_typed("other", Binary::from(b"missing"))
+            .unwrap();
+        assert_eq!(config, None);
+
+        // value of another type
+        let err = wrapper
+            .query_wasm_raw_typed::<u64, _, _>("other", Binary::from(b"config"))
+            .unwrap_err();
+        match err {
+            StdError::ParseErr { .. } => {}
+            e => panic!("Unexpected error: {:?}", e),
+        }
+    }
 }
@@ -8,6 +8,19 @@ use crate::coins::Coin;
 pub struct Env {
     pub block: BlockInfo,
     pub contract: ContractInfo,
+    /// Information on the transaction this message was executed in.
+    /// The field is unset when the contract is not executed as part of a transaction (e.g. in queries).
+    pub transaction: Option<TransactionInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, JsonSchema)]
+pub struct TransactionInfo {
+    /// The position of this transaction in the block. The first
+    /// transaction has index 0.
+    ///
+    /// This allows you to get a unique transaction identifier in this chain
+    /// using the pair (`env.block.height`, `env.transaction.index`).
+    pub index: u32,
 }
 
 #[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, JsonSchema)]
@@ -36,7 +49,9 @@ pub struct BlockInfo {
     /// #     },
     /// #     contract: ContractInfo {
     /// #         address: HumanAddr::from("contract"),
+    /// #         code_id: None,
     /// #     },
+    /// #     transaction: None,
     /// # };
     /// # extern crate chrono;
     /// use chrono::NaiveDateTime;
@@ -56,7 +71,9 @@ pub struct BlockInfo {
     /// #     },
     /// #     contract: ContractInfo {
     /// #         address: HumanAddr::from("contract"),
+    /// #         code_id: None,
     /// #     },
+    /// #     transaction: None,
     /// # };
     /// let millis = (env.block.time * 1_000) + (env.block.time_nanos / 1_000_000);
     /// ```
@@ -92,4 +109,7 @@ pub struct MessageInfo {
 #[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, JsonSchema)]
 pub struct ContractInfo {
     pub address: HumanAddr,
+    /// The ID of the code this contract is an instance of.
+    /// The field is unset when the environment does not provide it.
+    pub code_id: Option<u64>,
 }
@@ -3,15 +3,53 @@
 //! use cosmwasm_vm::testing::X
 use schemars::JsonSchema;
 use serde::{de::DeserializeOwned, Serialize};
+use std::cell::RefCell;
 use std::fmt;
 
 use cosmwasm_std::{ContractResult, Env, MessageInfo, QueryResponse, Response};
 
-use crate::calls::{call_handle, call_init, call_migrate, call_query};
+use crate::calls::{call_handle_raw, call_init_raw, call_migrate_raw, call_query};
 use crate::instance::Instance;
-use crate::serde::to_vec;
+use crate::serde::{from_slice, to_vec};
 use crate::{BackendApi, Querier, Storage};
 
+thread_local! {
+    static RESPONSE_SIZES: RefCell<Vec<usize>> = RefCell::new(Vec::new());
+}
+
+/// Returns the serialized size in bytes of every successful `Response` returned by `init`,
+/// `handle` and `migrate` in the current thread, in call order. Errors are not recorded.
+///
+/// Sizes are only recorded by these helpers, i.e. when a compiled contract is executed.
+/// Unit tests calling the contract's entry points directly do not record anything.
+///
+/// The test harness runs every test in its own thread by default, such that this
+/// only contains the results of the current test.
+pub fn recorded_response_sizes() -> Vec<usize> {
+    RESPONSE_SIZES.with(|sizes| sizes.borrow().clone())
+}
+
+/// Forgets all sizes recorded in the current thread
+pub fn clear_recorded_response_sizes() {
+    RESPONSE_SIZES.with(|sizes| sizes.borrow_mut().clear());
+}
+
+/// Deserializes a contract result and records the serialized size of its response
+fn record_response<U>(data: Vec<u8>) -> ContractResult<Response<U>>
+where
+    U: Serialize + DeserializeOwned + Clone + PartialEq + JsonSchema + fmt::Debug,
+{
+    let result: ContractResult<Response<U>> =
+        from_slice(&data).expect("Testing error: Could not parse contract result");
+    if let ContractResult::Ok(response) = &result {
+        let size = to_vec(response)
+            .expect("Testing error: Could not serialize response")
+            .len();
+        RESPONSE_SIZES.with(|sizes| sizes.borrow_mut().push(size));
+    }
+    result
+}
+
 // init mimicks the call signature of the smart contracts.
 // thus it moves env and msg rather than take them as reference.
 // this is inefficient here, but only used in test code
@@ -26,10 +64,12 @@ where
     S: Storage + 'static,
     Q: Querier + 'static,
     M: Serialize + JsonSchema,
-    U: DeserializeOwned + Clone + PartialEq + JsonSchema + fmt::Debug,
+    U: Serialize + DeserializeOwned + Clone + PartialEq + JsonSchema + fmt::Debug,
 {
     let serialized_msg = to_vec(&msg).expect("Testing error: Could not seralize request message");
-    call_init(instance, &env, &info, &serialized_msg).expect("VM error")
+    let env = to_vec(&env).expect("Testing error: Could not seralize env");
+    let info = to_vec(&info).expect("Testing error: Could not seralize info");
+    record_response(call_init_raw(instance, &env, &info, &serialized_msg).expect("VM error"))
 }
 
 // handle mimicks the call signature of the smart contracts.
@@ -46,10 +86,12 @@ where
     S: Storage + 'static,
     Q: Querier + 'static,
     M: Serialize + JsonSchema,
-    U: DeserializeOwned + Clone + PartialEq + JsonSchema + fmt::Debug,
+    U: Serialize + DeserializeOwned + Clone + PartialEq + JsonSchema + fmt::Debug,
 {
     let serialized_msg = to_vec(&msg).expect("Testing error: Could not seralize request message");
-    call_handle(instance, &env, &info, &serialized_msg).expect("VM error")
+    let env = to_vec(&env).expect("Testing error: Could not seralize env");
+    let info = to_vec(&info).expect("Testing error: Could not seralize info");
+    record_response(call_handle_raw(instance, &env, &info, &serialized_msg).expect("VM error"))
 }
 
 // migrate mimicks the call signature of the smart contracts.
@@ -65,10 +107,11 @@ where
     S: Storage + 'static,
     Q: Querier + 'static,
     M: Serialize + JsonSchema,
-    U: DeserializeOwned + Clone + PartialEq + JsonSchema + fmt::Debug,
+    U: Serialize + DeserializeOwned + Clone + PartialEq + JsonSchema + fmt::Debug,
 {
     let serialized_msg = to_vec(&msg).expect("Testing error: Could not seralize request message");
-    call_migrate(instance, &env, &serialized_msg).expect("VM error")
+    let env = to_vec(&env).expect("Testing error: Could not seralize env");
+    record_response(call_migrate_raw(instance, &env, &serialized_msg).expect("VM error"))
 }
 
 // query mimicks the call signature of the smart contracts.
@@ -88,3 +131,58 @@ where
     let serialized_msg = to_vec(&msg).expect("Testing error: Could not seralize request message");
     call_query(instance, &env, &serialized_msg).expect("VM error")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{mock_env, mock_info, mock_instance};
+    use cosmwasm_std::{coins, Empty, HumanAddr};
+
+    static CONTRACT: &[u8] = include_bytes!("../../testdata/hackatom.wasm");
+
+    #[derive(Serialize, JsonSchema)]
+    struct InitMsg {
+        verifier: HumanAddr,
+        beneficiary: HumanAddr,
+    }
+
+    #[derive(Serialize, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    enum HandleMsg {
+        Release {},
+    }
+
+    #[test]
+    fn init_and_handle_record_response_sizes() {
+        let mut instance = mock_instance(&CONTRACT, &[]);
+        assert_eq!(recorded_response_sizes(), Vec::<usize>::new());
+
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let msg = InitMsg {
+            verifier: HumanAddr::from("verifies"),
+            beneficiary: HumanAddr::from("benefits"),
+        };
+        let init_res: Response<Empty> = init(&mut instance, mock_env(), info, msg).unwrap();
+
+        let info = mock_info("verifies", &coins(15, "earth"));
+        let msg = HandleMsg::Release {};
+        let handle_res: Response<Empty> = handle(&mut instance, mock_env(), info, msg).unwrap();
+
+        // errors are not recorded
+        let info = mock_info("random", &[]);
+        let msg = HandleMsg::Release {};
+        let res: ContractResult<Response<Empty>> = handle(&mut instance, mock_env(), info, msg);
+        res.unwrap_err();
+
+        assert_eq!(
+            recorded_response_sizes(),
+            vec![
+                to_vec(&init_res).unwrap().len(),
+                to_vec(&handle_res).unwrap().len()
+            ]
+        );
+
+        clear_recorded_response_sizes();
+        assert_eq!(recorded_response_sizes(), Vec::<usize>::new());
+    }
+}
@@ -140,6 +140,9 @@ impl BackendApi for MockApi {
 /// You can submit as is to most contracts, or modify height/time if you want to
 /// test for expiration.
 ///
+/// The optional `env.contract.code_id` and `env.transaction` are unset, as they are
+/// on chains that do not provide them. Set them explicitly to test code relying on them.
+///
 /// This is intended for use in test code only.
 pub fn mock_env() -> Env {
     Env {
@@ -151,7 +154,9 @@ pub fn mock_env() -> Env {
         },
         contract: ContractInfo {
             address: HumanAddr::from(MOCK_CONTRACT_ADDR),
+            code_id: None,
         },
+        transaction: None,
     }
 }
 